@@ -0,0 +1,100 @@
+//! Génération complète, coût d'une étape et coût de `get_cell_layers` pour chaque algorithme
+//!
+//! `cargo bench --bench generators` ; filtrer avec un motif, par exemple
+//! `cargo bench --bench generators -- "generate/Prim"` ou `-- "/100$"`
+//! Les grilles de 4000² (16 millions de cellules) prennent plusieurs minutes au total
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use pathfinding::{AlgorithmKind, MazeGenerator};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZES: [usize; 3] = [100, 1000, 4000];
+const SEED: u64 = 42;
+
+fn started(kind: AlgorithmKind, size: usize) -> MazeGenerator {
+    let mut generator = MazeGenerator::new(size, size).unwrap();
+    generator.start_generation_with_seed(kind, SEED);
+    generator
+}
+
+// Génération complète, préparation de la grille comprise
+fn bench_generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    group.sample_size(10);
+
+    for size in SIZES {
+        for kind in AlgorithmKind::ALL {
+            group.bench_with_input(BenchmarkId::new(format!("{kind:?}"), size), &size, |b, &size| {
+                b.iter(|| {
+                    let mut generator = started(kind, size);
+                    while !generator.is_finished() {
+                        generator.generation_step().unwrap();
+                    }
+                    black_box(generator)
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+// Coût moyen d'un appel à generation_step, pendant une génération en cours
+// La préparation d'une nouvelle génération, quand la précédente se termine, n'est pas mesurée
+fn bench_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step");
+    group.sample_size(10);
+
+    for size in SIZES {
+        for kind in AlgorithmKind::ALL {
+            group.bench_with_input(BenchmarkId::new(format!("{kind:?}"), size), &size, |b, &size| {
+                b.iter_custom(|iterations| {
+                    let mut generator = started(kind, size);
+                    let mut elapsed = Duration::ZERO;
+                    let mut done = 0;
+                    while done < iterations {
+                        if generator.is_finished() {
+                            generator = started(kind, size);
+                        }
+                        let start = Instant::now();
+                        while done < iterations && !generator.is_finished() {
+                            black_box(generator.generation_step().unwrap());
+                            done += 1;
+                        }
+                        elapsed += start.elapsed();
+                    }
+                    elapsed
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+// Coût de get_cell_layers à mi-parcours (une étape par cellule de la moitié de la grille)
+fn bench_cell_layers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cell_layers");
+    group.sample_size(10);
+
+    for size in SIZES {
+        for kind in AlgorithmKind::ALL {
+            // Préparé au premier appel seulement : rien n'est calculé pour un benchmark filtré
+            let mut generator = None;
+            group.bench_with_input(BenchmarkId::new(format!("{kind:?}"), size), &size, |b, &size| {
+                let generator = generator.get_or_insert_with(|| {
+                    let mut generator = started(kind, size);
+                    generator.generation_steps(size * size / 2).unwrap();
+                    generator
+                });
+                b.iter(|| black_box(generator.get_cell_layers()))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate, bench_step, bench_cell_layers);
+criterion_main!(benches);
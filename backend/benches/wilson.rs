@@ -0,0 +1,83 @@
+//! Wilson : tables d'index (version actuelle) contre recherches linéaires (version d'origine)
+//!
+//! `cargo bench --bench wilson`
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use pathfinding::generators::WilsonGenerator;
+use pathfinding::{GenerationAlgorithm, MazeGrid, MazeRng, Topology};
+use rand::Rng;
+use std::hint::black_box;
+
+fn generate(width: usize, height: usize) -> MazeGrid {
+    let mut grid = MazeGrid::new(width, height);
+    let mut generator = WilsonGenerator::with_seed(42);
+    generator.start(&mut grid);
+    while !GenerationAlgorithm::<MazeGrid>::is_finished(&generator) {
+        generator.step(&mut grid);
+    }
+    grid
+}
+
+// Version d'origine, gardée comme point de comparaison : retrait dans la liste des cellules
+// restantes et détection des boucles par parcours linéaire
+fn generate_linear(width: usize, height: usize) -> MazeGrid {
+    let mut grid = MazeGrid::new(width, height);
+    let mut rng = MazeRng::from_seed(42);
+    let total_cells = grid.cell_count();
+    grid.fill_walls();
+
+    let mut in_maze = vec![false; total_cells];
+    let mut remaining_cells: Vec<usize> = (0..total_cells).collect();
+    let start = remaining_cells.swap_remove(rng.random_range(0..total_cells));
+    in_maze[start] = true;
+
+    while !remaining_cells.is_empty() {
+        let mut path = vec![remaining_cells[rng.random_range(0..remaining_cells.len())]];
+        while let Some(&current) = path.last().filter(|&&cell| !in_maze[cell]) {
+            let neighbors = grid.neighbors(current);
+            let next = neighbors[rng.random_range(0..neighbors.len())];
+            match path.iter().position(|&cell| cell == next) {
+                Some(loop_index) => path.truncate(loop_index + 1),
+                None => path.push(next),
+            }
+        }
+
+        while let Some(current) = path.pop() {
+            in_maze[current] = true;
+            if let Some(position) = remaining_cells.iter().position(|&cell| cell == current) {
+                remaining_cells.swap_remove(position);
+            }
+            if let Some(&previous) = path.last() {
+                grid.link(current, previous);
+            }
+        }
+    }
+
+    grid
+}
+
+fn bench_wilson(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wilson");
+    group.sample_size(10);
+
+    for size in [32, 64, 128] {
+        group.bench_with_input(BenchmarkId::new("index", size), &size, |b, &size| {
+            b.iter(|| black_box(generate(size, size)))
+        });
+        group.bench_with_input(BenchmarkId::new("linear", size), &size, |b, &size| {
+            b.iter(|| black_box(generate_linear(size, size)))
+        });
+    }
+
+    // Grandes grilles : version actuelle seulement, la version linéaire y est quadratique
+    for size in [512, 1000] {
+        group.bench_with_input(BenchmarkId::new("index", size), &size, |b, &size| {
+            b.iter(|| black_box(generate(size, size)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_wilson);
+criterion_main!(benches);
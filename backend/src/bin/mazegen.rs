@@ -0,0 +1,256 @@
+//! Génération de labyrinthes en ligne de commande, sans navigateur
+//!
+//! Exemple :
+//! `mazegen --width 40 --height 20 --algorithm wilson --seed 42 --count 10 --format svg --output niveaux/niveau.svg`
+//! produit `niveaux/niveau-0.svg` à `niveaux/niveau-9.svg`
+
+use pathfinding::export::{self, PdfOptions};
+use pathfinding::MazeError;
+use pathfinding::generators::{Algorithm, AlgorithmKind, GenerationAlgorithm};
+use pathfinding::maze_generator::MAX_DIMENSION;
+use pathfinding::maze_grid::MazeGrid;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage : mazegen [options]
+
+Options :
+  -w, --width <N>        Largeur de la grille (défaut : 30)
+  -H, --height <N>       Hauteur de la grille (défaut : 30)
+  -a, --algorithm <NOM>  backtracking, prim, kruskal, wilson, division (défaut : backtracking)
+  -s, --seed <N>         Graine du premier labyrinthe, incrémentée pour les suivants
+  -n, --count <N>        Nombre de labyrinthes à générer, au moins 1 (défaut : 1)
+  -f, --format <FORMAT>  ascii, svg, json, binary, pdf (défaut : ascii)
+  -o, --output <CHEMIN>  Fichier de sortie (défaut : sortie standard, obligatoire si --count > 1)
+  -h, --help             Afficher cette aide";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    Svg,
+    Json,
+    Binary,
+    Pdf,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "ascii" | "txt" => Ok(Format::Ascii),
+            "svg" => Ok(Format::Svg),
+            "json" => Ok(Format::Json),
+            "binary" | "bin" => Ok(Format::Binary),
+            "pdf" => Ok(Format::Pdf),
+            _ => Err(format!("Format inconnu : {}", name)),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Ascii => "txt",
+            Format::Svg => "svg",
+            Format::Json => "json",
+            Format::Binary => "bin",
+            Format::Pdf => "pdf",
+        }
+    }
+
+    fn encode(&self, grid: &MazeGrid) -> Result<Vec<u8>, MazeError> {
+        Ok(match self {
+            Format::Ascii => export::to_ascii(grid).into_bytes(),
+            Format::Svg => export::to_svg(grid, 16.0).into_bytes(),
+            Format::Json => {
+                let mut json = export::to_json(grid);
+                json.push('\n');
+                json.into_bytes()
+            }
+            Format::Binary => export::to_bytes(grid),
+            Format::Pdf => export::export_pdf(grid, &PdfOptions::default())?,
+        })
+    }
+}
+
+struct Options {
+    width: usize,
+    height: usize,
+    algorithm: AlgorithmKind,
+    seed: Option<u64>,
+    count: usize,
+    format: Format,
+    output: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            width: 30,
+            height: 30,
+            algorithm: AlgorithmKind::Backtracking,
+            seed: None,
+            count: 1,
+            format: Format::Ascii,
+            output: None,
+        }
+    }
+}
+
+/// Analyse les arguments ; retourne None si l'aide a été demandée
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(None);
+        }
+
+        let mut value = || args.next().ok_or_else(|| format!("Valeur manquante pour {}", arg));
+        match arg.as_str() {
+            "-w" | "--width" => options.width = parse_number(&value()?)?,
+            "-H" | "--height" => options.height = parse_number(&value()?)?,
+            "-a" | "--algorithm" => options.algorithm = value()?.parse()?,
+            "-s" | "--seed" => options.seed = Some(parse_number(&value()?)?),
+            "-n" | "--count" => options.count = parse_number(&value()?)?,
+            "-f" | "--format" => options.format = Format::parse(&value()?)?,
+            "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("Option inconnue : {}", arg)),
+        }
+    }
+
+    let valid = |dimension| (1..=MAX_DIMENSION).contains(&dimension);
+    if !valid(options.width) || !valid(options.height) {
+        return Err(MazeError::InvalidDimensions { width: options.width, height: options.height }.to_string());
+    }
+
+    if options.count == 0 {
+        return Err(String::from("--count doit valoir au moins 1"));
+    }
+
+    // Plusieurs documents à la suite sur la sortie standard ne formeraient pas un fichier valide
+    if options.count > 1 && options.output.is_none() {
+        return Err(String::from("--output est obligatoire pour générer plusieurs labyrinthes"));
+    }
+
+    Ok(Some(options))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Nombre invalide : {}", value))
+}
+
+/// Chemin du i-ème fichier : `niveau.svg` devient `niveau-3.svg` quand il y en a plusieurs
+fn output_path(base: &Path, index: usize, count: usize, format: Format) -> PathBuf {
+    if count == 1 {
+        return base.to_path_buf();
+    }
+
+    let stem = base.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = base
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format.extension().to_string());
+    base.with_file_name(format!("{}-{}.{}", stem, index, extension))
+}
+
+fn generate(options: &Options, seed: u64) -> MazeGrid {
+    let mut grid = MazeGrid::new(options.width, options.height);
+    let mut algorithm = Algorithm::with_seed(options.algorithm, seed);
+
+    algorithm.start(&mut grid);
+    while !algorithm.is_finished() {
+        algorithm.step(&mut grid);
+    }
+
+    grid
+}
+
+fn run(options: Options) -> Result<(), String> {
+    // Sans graine explicite, en tirer une et l'afficher pour pouvoir reproduire le lot
+    let first_seed = options.seed.unwrap_or_else(rand::random);
+    eprintln!(
+        "{} labyrinthe(s) {}x{} - {} - graine {}",
+        options.count,
+        options.width,
+        options.height,
+        Algorithm::new(options.algorithm).get_name(),
+        first_seed
+    );
+
+    let stdout = std::io::stdout();
+    for index in 0..options.count {
+        let seed = first_seed.wrapping_add(index as u64);
+        let grid = generate(&options, seed);
+        let bytes = options.format.encode(&grid).map_err(|e| e.to_string())?;
+
+        match &options.output {
+            Some(base) => {
+                let path = output_path(base, index, options.count, options.format);
+                std::fs::write(&path, bytes).map_err(|e| format!("{} : {}", path.display(), e))?;
+            }
+            None => stdout.lock().write_all(&bytes).map_err(|e| e.to_string())?,
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Option<Options>, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn accepts_sizes_up_to_max_dimension() {
+        let options = parse(&format!("-w {} -H 2", MAX_DIMENSION)).unwrap().unwrap();
+        assert_eq!((options.width, options.height), (MAX_DIMENSION, 2));
+
+        assert!(parse(&format!("-w {} -H 2", MAX_DIMENSION + 1)).is_err());
+        assert!(parse("-w 0").is_err());
+    }
+
+    #[test]
+    fn requires_output_for_several_mazes() {
+        assert!(parse("-n 3 -f pdf").is_err());
+        assert!(parse("-n 3 -f pdf -o niveau.pdf").unwrap().is_some());
+        assert!(parse("-n 1 -f pdf").unwrap().is_some());
+    }
+
+    #[test]
+    fn rejects_zero_count() {
+        assert!(parse("-n 0").is_err());
+        assert!(parse("-n 0 -o niveau.txt").is_err());
+    }
+
+    #[test]
+    fn short_h_is_help() {
+        assert!(parse("-h").unwrap().is_none());
+        assert!(parse("-w 5 --help").unwrap().is_none());
+        assert_eq!(parse("-H 7").unwrap().map(|options| options.height), Some(7));
+    }
+}
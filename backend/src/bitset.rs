@@ -0,0 +1,221 @@
+//! Ensemble de bits compact, utilisé pour stocker les murs de `MazeGrid`
+//!
+//! Un bit par cellule, rangés dans des mots de 64 bits (bit de poids faible en premier) :
+//! remplissage, copie et comparaison se font un mot à la fois.
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct BitSet {
+    words: Vec<u64>,
+    // Nombre de bits utiles ; les bits au-delà restent toujours à 0
+    len: usize,
+}
+
+impl BitSet {
+    pub(crate) fn new(len: usize) -> Self {
+        Self { words: vec![0; len.div_ceil(WORD_BITS)], len }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn get(&self, index: usize) -> bool {
+        self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 != 0
+    }
+
+    pub(crate) fn set(&mut self, index: usize, value: bool) {
+        let mask = 1 << (index % WORD_BITS);
+        let word = &mut self.words[index / WORD_BITS];
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    pub(crate) fn fill(&mut self, value: bool) {
+        self.words.fill(if value { u64::MAX } else { 0 });
+        self.clear_tail();
+    }
+
+    /// Bits sous forme d'octets, bit de poids faible en premier
+    /// L'ordre correspond à celui de l'export binaire sur une machine little-endian (dont WASM)
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        // SAFETY: un u64 est fait de 8 octets initialisés, et u8 n'a pas de contrainte d'alignement
+        unsafe { std::slice::from_raw_parts(self.words.as_ptr().cast::<u8>(), self.words.len() * 8) }
+    }
+
+    /// Octets little-endian, quelle que soit la machine, tronqués au dernier bit utile
+    pub(crate) fn to_le_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.words.iter().flat_map(|word| word.to_le_bytes()).take(self.len.div_ceil(8))
+    }
+
+    /// Relit des octets écrits par `to_le_bytes`
+    pub(crate) fn from_le_bytes(bytes: &[u8], len: usize) -> Self {
+        let mut bitset = Self::new(len);
+        for (word, chunk) in bitset.words.iter_mut().zip(bytes.chunks(8)) {
+            let mut buffer = [0; 8];
+            buffer[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_le_bytes(buffer);
+        }
+        bitset.clear_tail();
+        bitset
+    }
+
+    /// Copie `count` bits de `source` (à partir de `source_start`) à partir de `start`, par mots entiers
+    pub(crate) fn copy_from(&mut self, start: usize, source: &BitSet, source_start: usize, count: usize) {
+        let mut done = 0;
+        while done < count {
+            let chunk = (count - done).min(WORD_BITS);
+            let bits = source.read(source_start + done, chunk);
+            self.write(start + done, chunk, bits);
+            done += chunk;
+        }
+    }
+
+    /// Index des bits qui diffèrent entre deux ensembles de même taille, dans l'ordre croissant
+    pub(crate) fn differences<'a>(&'a self, other: &'a BitSet) -> impl Iterator<Item = usize> + 'a {
+        self.words.iter().zip(&other.words).enumerate().flat_map(|(word_index, (a, b))| {
+            let mut diff = a ^ b;
+            std::iter::from_fn(move || {
+                if diff == 0 {
+                    return None;
+                }
+                let bit = diff.trailing_zeros() as usize;
+                diff &= diff - 1;
+                Some(word_index * WORD_BITS + bit)
+            })
+        })
+    }
+
+    fn clear_tail(&mut self) {
+        let used = self.len % WORD_BITS;
+        if used != 0
+            && let Some(last) = self.words.last_mut()
+        {
+            *last &= low_mask(used);
+        }
+    }
+
+    // Lit `count` bits (au plus 64) à partir de `start`, éventuellement à cheval sur deux mots
+    fn read(&self, start: usize, count: usize) -> u64 {
+        let (word, shift) = (start / WORD_BITS, start % WORD_BITS);
+        let mut bits = self.words[word] >> shift;
+        if shift != 0 && shift + count > WORD_BITS {
+            bits |= self.words[word + 1] << (WORD_BITS - shift);
+        }
+        bits & low_mask(count)
+    }
+
+    // Écrit les `count` bits de poids faible de `bits` à partir de `start`
+    fn write(&mut self, start: usize, count: usize, bits: u64) {
+        let (word, shift) = (start / WORD_BITS, start % WORD_BITS);
+        let mask = low_mask(count);
+        self.words[word] = (self.words[word] & !(mask << shift)) | (bits << shift);
+        if shift != 0 && shift + count > WORD_BITS {
+            let high_mask = low_mask(shift + count - WORD_BITS);
+            self.words[word + 1] = (self.words[word + 1] & !high_mask) | (bits >> (WORD_BITS - shift));
+        }
+    }
+}
+
+// Masque des `count` bits de poids faible (1 à 64)
+fn low_mask(count: usize) -> u64 {
+    if count >= WORD_BITS { u64::MAX } else { (1 << count) - 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Motif irrégulier, pour que chaque décalage donne un résultat différent
+    fn pattern(len: usize, seed: usize) -> BitSet {
+        let mut bitset = BitSet::new(len);
+        for index in 0..len {
+            bitset.set(index, (index * 7919 + seed) % 13 < 6);
+        }
+        bitset
+    }
+
+    fn bits(bitset: &BitSet) -> Vec<bool> {
+        (0..bitset.len()).map(|index| bitset.get(index)).collect()
+    }
+
+    #[test]
+    fn set_get_and_fill_keep_the_tail_clear() {
+        let mut bitset = BitSet::new(70);
+        bitset.set(0, true);
+        bitset.set(64, true);
+        bitset.set(69, true);
+        assert!(bitset.get(0) && bitset.get(64) && bitset.get(69) && !bitset.get(1));
+        bitset.set(64, false);
+        assert!(!bitset.get(64));
+
+        // Les bits au-delà de `len` restent à 0 : l'égalité et l'export n'en dépendent pas
+        bitset.fill(true);
+        assert_eq!(bitset.words[1], (1 << 6) - 1);
+        assert!(bits(&bitset).iter().all(|&bit| bit));
+        bitset.fill(false);
+        assert_eq!(bitset, BitSet::new(70));
+    }
+
+    #[test]
+    fn copy_from_matches_a_bit_by_bit_copy() {
+        let source = pattern(200, 3);
+        for source_start in 0..70 {
+            for start in 0..70 {
+                for count in [0, 1, 17, 63, 64, 65, 128] {
+                    let mut copied = pattern(200, 5);
+                    let mut expected = bits(&copied);
+                    copied.copy_from(start, &source, source_start, count);
+                    for i in 0..count {
+                        expected[start + i] = source.get(source_start + i);
+                    }
+                    assert_eq!(bits(&copied), expected, "copie de {} bits de {} vers {}", count, source_start, start);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn le_bytes_round_trip() {
+        for len in [0, 1, 8, 63, 64, 65, 130] {
+            let bitset = pattern(len, 1);
+            let bytes: Vec<u8> = bitset.to_le_bytes().collect();
+            assert_eq!(bytes.len(), len.div_ceil(8));
+            assert_eq!(BitSet::from_le_bytes(&bytes, len), bitset);
+        }
+
+        // Bits au-delà de `len` ignorés à la lecture
+        assert_eq!(BitSet::from_le_bytes(&[0xff], 3), {
+            let mut bitset = BitSet::new(3);
+            bitset.fill(true);
+            bitset
+        });
+    }
+
+    #[test]
+    fn as_bytes_covers_whole_words() {
+        let bitset = pattern(130, 2);
+        let bytes = bitset.as_bytes();
+        assert_eq!(bytes.len(), 3 * 8);
+        for index in 0..130 {
+            assert_eq!(bytes[index / 8] >> (index % 8) & 1 != 0, bitset.get(index), "bit {}", index);
+        }
+        // Bits de remplissage à 0
+        assert!((130..bytes.len() * 8).all(|index| bytes[index / 8] >> (index % 8) & 1 == 0));
+        assert!(BitSet::new(0).as_bytes().is_empty());
+    }
+
+    #[test]
+    fn differences_lists_changed_bits_in_order() {
+        let (a, mut b) = (pattern(150, 0), pattern(150, 0));
+        assert_eq!(a.differences(&b).count(), 0);
+        for index in [0, 63, 64, 100, 149] {
+            b.set(index, !b.get(index));
+        }
+        assert_eq!(a.differences(&b).collect::<Vec<_>>(), vec![0, 63, 64, 100, 149]);
+    }
+}
@@ -0,0 +1,115 @@
+//! Briques des formats binaires de la crate (enregistrements, instantanés)
+//!
+//! Les entiers sont des varints LEB128 ; les cellules sont vérifiées à la lecture,
+//! pour qu'un fichier corrompu soit refusé au lieu de faire paniquer la génération.
+
+use crate::export::DecodeError;
+
+pub(crate) fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+// Cellule facultative : 0 pour None, index + 1 sinon
+pub(crate) fn write_option(bytes: &mut Vec<u8>, value: Option<usize>) {
+    write_varint(bytes, value.map_or(0, |value| value + 1));
+}
+
+// Longueur puis valeurs
+pub(crate) fn write_cells(bytes: &mut Vec<u8>, cells: &[usize]) {
+    write_varint(bytes, cells.len());
+    for &cell in cells {
+        write_varint(bytes, cell);
+    }
+}
+
+// Longueur puis un bit par valeur, bit de poids faible en premier
+pub(crate) fn write_bools(bytes: &mut Vec<u8>, values: &[bool]) {
+    write_varint(bytes, values.len());
+    for chunk in values.chunks(8) {
+        bytes.push(chunk.iter().enumerate().fold(0, |byte, (bit, &value)| byte | (u8::from(value) << bit)));
+    }
+}
+
+// Lecture séquentielle avec détection des données tronquées
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self.position.checked_add(len).ok_or(DecodeError::Truncated)?;
+        let slice = self.bytes.get(self.position..end).ok_or(DecodeError::Truncated)?;
+        self.position = end;
+        Ok(slice)
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> Result<bool, DecodeError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidState),
+        }
+    }
+
+    pub(crate) fn varint(&mut self) -> Result<usize, DecodeError> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::InvalidHeader)
+    }
+
+    /// Index de cellule, inférieur à `cell_count`
+    pub(crate) fn cell(&mut self, cell_count: usize) -> Result<usize, DecodeError> {
+        let cell = self.varint()?;
+        if cell < cell_count { Ok(cell) } else { Err(DecodeError::InvalidState) }
+    }
+
+    /// Relit `write_option`
+    pub(crate) fn option(&mut self, cell_count: usize) -> Result<Option<usize>, DecodeError> {
+        match self.varint()? {
+            0 => Ok(None),
+            value if value <= cell_count => Ok(Some(value - 1)),
+            _ => Err(DecodeError::InvalidState),
+        }
+    }
+
+    /// Longueur d'une liste dont chaque élément occupe au moins un octet
+    /// Bornée par les données restantes : une longueur corrompue n'entraîne pas d'allocation démesurée
+    pub(crate) fn list_len(&mut self) -> Result<usize, DecodeError> {
+        let len = self.varint()?;
+        if len <= self.bytes.len() - self.position { Ok(len) } else { Err(DecodeError::Truncated) }
+    }
+
+    /// Relit `write_cells`
+    pub(crate) fn cells(&mut self, cell_count: usize) -> Result<Vec<usize>, DecodeError> {
+        let len = self.list_len()?;
+        (0..len).map(|_| self.cell(cell_count)).collect()
+    }
+
+    /// Relit `write_bools`, qui doit contenir exactement `len` valeurs
+    pub(crate) fn bools(&mut self, len: usize) -> Result<Vec<bool>, DecodeError> {
+        if self.varint()? != len {
+            return Err(DecodeError::InvalidState);
+        }
+        let packed = self.take(len.div_ceil(8))?;
+        Ok((0..len).map(|i| packed[i / 8] >> (i % 8) & 1 != 0).collect())
+    }
+}
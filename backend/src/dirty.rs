@@ -0,0 +1,153 @@
+//! Suivi des zones de la grille modifiées depuis le dernier rendu
+//!
+//! La grille est découpée en tuiles carrées de `tile_size` cellules de côté ;
+//! chaque écriture de mur marque les tuiles touchées, que le rendu récupère
+//! ensuite pour ne redessiner qu'elles.
+
+/// Zone rectangulaire à redessiner, en cellules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyTile {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Tuiles modifiées, sans doublon
+#[derive(Debug, Clone)]
+pub(crate) struct DirtyTiles {
+    tile_size: usize,
+    tiles_x: usize,
+    tiles_y: usize,
+    // Marqueur par tuile, pour ne l'ajouter qu'une fois à `pending`
+    marked: Vec<bool>,
+    pending: Vec<usize>,
+}
+
+impl DirtyTiles {
+    /// Toutes les tuiles sont marquées au départ : rien n'a encore été dessiné
+    pub(crate) fn new(width: usize, height: usize, tile_size: usize) -> Self {
+        let tile_size = tile_size.max(1);
+        let tiles_x = width.div_ceil(tile_size);
+        let tiles_y = height.div_ceil(tile_size);
+        let mut tiles = Self {
+            tile_size,
+            tiles_x,
+            tiles_y,
+            marked: vec![false; tiles_x * tiles_y],
+            pending: Vec::new(),
+        };
+        tiles.mark_all();
+        tiles
+    }
+
+    pub(crate) fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    /// Marque la tuile qui contient la cellule (x, y)
+    pub(crate) fn mark_cell(&mut self, x: usize, y: usize) {
+        let (tile_x, tile_y) = (x / self.tile_size, y / self.tile_size);
+        if tile_x >= self.tiles_x || tile_y >= self.tiles_y {
+            return;
+        }
+
+        let tile = tile_y * self.tiles_x + tile_x;
+        if !self.marked[tile] {
+            self.marked[tile] = true;
+            self.pending.push(tile);
+        }
+    }
+
+    pub(crate) fn mark_all(&mut self) {
+        self.pending.clear();
+        self.pending.extend(0..self.marked.len());
+        self.marked.fill(true);
+    }
+
+    /// Retourne les tuiles marquées, bornées à la grille, et remet le suivi à zéro
+    pub(crate) fn take(&mut self, width: usize, height: usize) -> Vec<DirtyTile> {
+        let tile_size = self.tile_size;
+        let tiles_x = self.tiles_x;
+        self.pending
+            .drain(..)
+            .map(|tile| {
+                self.marked[tile] = false;
+                let (x, y) = (tile % tiles_x * tile_size, tile / tiles_x * tile_size);
+                DirtyTile { x, y, width: tile_size.min(width - x), height: tile_size.min(height - y) }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::WallType;
+    use crate::maze_grid::MazeGrid;
+
+    fn tile(x: usize, y: usize, width: usize, height: usize) -> DirtyTile {
+        DirtyTile { x, y, width, height }
+    }
+
+    #[test]
+    fn everything_is_dirty_at_first_and_edge_tiles_are_clipped() {
+        let mut tiles = DirtyTiles::new(10, 5, 4);
+        assert_eq!(
+            tiles.take(10, 5),
+            vec![tile(0, 0, 4, 4), tile(4, 0, 4, 4), tile(8, 0, 2, 4), tile(0, 4, 4, 1), tile(4, 4, 4, 1), tile(8, 4, 2, 1)]
+        );
+        assert!(tiles.take(10, 5).is_empty());
+    }
+
+    #[test]
+    fn marked_tiles_are_returned_once_in_marking_order() {
+        let mut tiles = DirtyTiles::new(10, 5, 4);
+        tiles.take(10, 5);
+
+        tiles.mark_cell(9, 4);
+        tiles.mark_cell(1, 1);
+        tiles.mark_cell(8, 4);
+        tiles.mark_cell(2, 3);
+        // Hors de la grille : ignoré
+        tiles.mark_cell(12, 0);
+        tiles.mark_cell(0, 8);
+        assert_eq!(tiles.take(10, 5), vec![tile(8, 4, 2, 1), tile(0, 0, 4, 4)]);
+
+        // Une tuile rendue peut être marquée à nouveau
+        tiles.mark_cell(1, 1);
+        assert_eq!(tiles.take(10, 5), vec![tile(0, 0, 4, 4)]);
+    }
+
+    #[test]
+    fn zero_tile_size_means_single_cells() {
+        let mut tiles = DirtyTiles::new(2, 2, 0);
+        assert_eq!(tiles.tile_size(), 1);
+        assert_eq!(tiles.take(2, 2).len(), 4);
+    }
+
+    #[test]
+    fn wall_writes_mark_both_sides() {
+        let mut grid = MazeGrid::new(8, 8);
+        grid.enable_dirty_tracking(4);
+        grid.take_dirty_tiles();
+
+        // Mur vertical sur la frontière de deux tuiles
+        grid.set_wall(3, 0, WallType::Vertical, true);
+        assert_eq!(grid.take_dirty_tiles(), vec![tile(0, 0, 4, 4), tile(4, 0, 4, 4)]);
+
+        // Mur inchangé : rien à redessiner
+        grid.set_wall(3, 0, WallType::Vertical, true);
+        assert!(grid.take_dirty_tiles().is_empty());
+
+        // Raccord torique : la cellule de l'autre bord est aussi marquée
+        grid.set_wrap(false, true);
+        grid.take_dirty_tiles();
+        grid.set_wall(5, 7, WallType::Horizontal, true);
+        assert_eq!(grid.take_dirty_tiles(), vec![tile(4, 4, 4, 4), tile(4, 0, 4, 4)]);
+
+        grid.disable_dirty_tracking();
+        grid.set_wall(0, 0, WallType::Vertical, true);
+        assert!(grid.take_dirty_tiles().is_empty());
+    }
+}
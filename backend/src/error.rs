@@ -0,0 +1,50 @@
+use crate::export::DecodeError;
+use crate::maze_generator::MAX_DIMENSION;
+use std::fmt;
+
+/// Erreurs de l'API du générateur ; côté WASM, elles deviennent des exceptions JS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    /// Largeur ou hauteur nulle, ou supérieure à `MAX_DIMENSION`
+    InvalidDimensions { width: usize, height: usize },
+    /// Cellule hors de la grille
+    OutOfBounds { x: usize, y: usize },
+    /// Données importées illisibles (labyrinthe ou enregistrement)
+    Decode(DecodeError),
+    /// Étape demandée alors qu'aucune génération n'a été démarrée (ou après un redimensionnement)
+    NotStarted,
+    /// Instantané d'un algorithme externe, dont l'état n'est pas connu de la crate
+    SnapshotUnsupported(&'static str),
+    /// Format de papier ou marges inutilisables pour l'export PDF (nuls, négatifs, non finis...)
+    InvalidPageSize,
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::InvalidDimensions { width, height } => {
+                write!(f, "Dimensions invalides : {} x {} (de 1 à {})", width, height, MAX_DIMENSION)
+            }
+            MazeError::OutOfBounds { x, y } => write!(f, "Cellule ({}, {}) hors de la grille", x, y),
+            MazeError::Decode(error) => write!(f, "Import invalide : {}", error),
+            MazeError::NotStarted => write!(f, "Aucune génération en cours"),
+            MazeError::SnapshotUnsupported(name) => write!(f, "L'état de l'algorithme {} ne peut pas être sauvegardé", name),
+            MazeError::InvalidPageSize => write!(f, "Format de page invalide : dimensions et marges doivent être positives et laisser de la place au labyrinthe"),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MazeError::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for MazeError {
+    fn from(error: DecodeError) -> Self {
+        MazeError::Decode(error)
+    }
+}
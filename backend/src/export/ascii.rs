@@ -0,0 +1,70 @@
+use crate::generators::WallType;
+use crate::maze_grid::{Crossing, MazeGrid};
+use crate::topology::LayeredGrid;
+
+/// Représentation texte du labyrinthe, une cellule = 3 caractères de large
+///
+/// ```text
+/// +---+---+
+/// |       |
+/// +   +---+
+/// |       |
+/// +---+---+
+/// ```
+/// Les croisements des labyrinthes tissés sont marqués par le sens du couloir supérieur (`|` ou `-`)
+pub fn to_ascii(grid: &MazeGrid) -> String {
+    render(grid, |x, y| match grid.crossing(x, y) {
+        Some(Crossing::Horizontal) => '|',
+        Some(Crossing::Vertical) => '-',
+        None => ' ',
+    })
+}
+
+/// Un étage d'une grille 3D, les escaliers étant marqués au centre des cellules :
+/// `U` vers le haut, `D` vers le bas, `X` dans les deux sens ; None au-delà du dernier étage
+pub fn floor_to_ascii(grid: &LayeredGrid, z: usize) -> Option<String> {
+    Some(render(grid.floor(z)?, |x, y| {
+        match (grid.has_stairs_up(x, y, z), grid.has_stairs_down(x, y, z)) {
+            (true, true) => 'X',
+            (true, false) => 'U',
+            (false, true) => 'D',
+            (false, false) => ' ',
+        }
+    }))
+}
+
+/// `marker` donne le caractère affiché au centre de chaque cellule
+fn render(grid: &MazeGrid, marker: impl Fn(usize, usize) -> char) -> String {
+    let mut output = String::with_capacity((grid.width * 4 + 2) * (grid.height * 2 + 1));
+
+    // Bordure supérieure : en mode torique, elle reprend les murs du bas de la dernière ligne
+    output.push('+');
+    for x in 0..grid.width {
+        let wall = grid.height == 0 || grid.is_closed(x, grid.height - 1, WallType::Horizontal);
+        output.push_str(if wall { "---+" } else { "   +" });
+    }
+    output.push('\n');
+
+    for y in 0..grid.height {
+        // Ligne des cellules avec les murs verticaux ; la bordure gauche reprend celle de droite
+        let left_wall = grid.is_closed(grid.width.saturating_sub(1), y, WallType::Vertical);
+        output.push(if left_wall { '|' } else { ' ' });
+        for x in 0..grid.width {
+            let wall = grid.is_closed(x, y, WallType::Vertical);
+            output.push(' ');
+            output.push(marker(x, y));
+            output.push_str(if wall { " |" } else { "  " });
+        }
+        output.push('\n');
+
+        // Ligne des murs horizontaux
+        output.push('+');
+        for x in 0..grid.width {
+            let wall = grid.is_closed(x, y, WallType::Horizontal);
+            output.push_str(if wall { "---+" } else { "   +" });
+        }
+        output.push('\n');
+    }
+
+    output
+}
@@ -0,0 +1,84 @@
+use crate::bitset::BitSet;
+use crate::maze_grid::MazeGrid;
+use std::fmt;
+
+/// Signature en tête de chaque fichier binaire
+const MAGIC: &[u8; 4] = b"MAZE";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 4 + 1 + 4 + 4;
+
+/// Erreurs possibles lors de la lecture d'un labyrinthe binaire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidHeader,
+    UnsupportedVersion(u8),
+    InvalidDimensions,
+    Truncated,
+    /// Valeurs incohérentes (cellule hors de la grille, état d'algorithme impossible...)
+    InvalidState,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHeader => write!(f, "En-tête invalide"),
+            DecodeError::UnsupportedVersion(version) => write!(f, "Version non supportée : {}", version),
+            DecodeError::InvalidDimensions => write!(f, "Dimensions invalides"),
+            DecodeError::Truncated => write!(f, "Données tronquées"),
+            DecodeError::InvalidState => write!(f, "Données incohérentes"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Format binaire compact :
+/// - "MAZE" + version (1 octet)
+/// - largeur et hauteur (u32 little-endian)
+/// - murs verticaux puis horizontaux, 1 bit par cellule (bit de poids faible en premier)
+pub fn to_bytes(grid: &MazeGrid) -> Vec<u8> {
+    let bit_len = packed_len(grid.width * grid.height);
+    let mut bytes = Vec::with_capacity(HEADER_LEN + 2 * bit_len);
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&(grid.width as u32).to_le_bytes());
+    bytes.extend_from_slice(&(grid.height as u32).to_le_bytes());
+    bytes.extend(grid.vertical_walls.to_le_bytes());
+    bytes.extend(grid.horizontal_walls.to_le_bytes());
+
+    bytes
+}
+
+/// Relit une grille écrite par `to_bytes`
+pub fn from_bytes(bytes: &[u8]) -> Result<MazeGrid, DecodeError> {
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != MAGIC {
+        return Err(DecodeError::InvalidHeader);
+    }
+    if bytes[4] != VERSION {
+        return Err(DecodeError::UnsupportedVersion(bytes[4]));
+    }
+
+    let width = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+    let height = u32::from_le_bytes([bytes[9], bytes[10], bytes[11], bytes[12]]) as usize;
+    let Some(total_cells) = width.checked_mul(height).filter(|&cells| cells > 0) else {
+        return Err(DecodeError::InvalidDimensions);
+    };
+
+    let bit_len = packed_len(total_cells);
+    if bytes.len() < HEADER_LEN + 2 * bit_len {
+        return Err(DecodeError::Truncated);
+    }
+
+    let mut grid = MazeGrid::new(width, height);
+    let vertical = &bytes[HEADER_LEN..HEADER_LEN + bit_len];
+    let horizontal = &bytes[HEADER_LEN + bit_len..HEADER_LEN + 2 * bit_len];
+    grid.vertical_walls = BitSet::from_le_bytes(vertical, total_cells);
+    grid.horizontal_walls = BitSet::from_le_bytes(horizontal, total_cells);
+
+    Ok(grid)
+}
+
+fn packed_len(bits: usize) -> usize {
+    bits.div_ceil(8)
+}
@@ -0,0 +1,27 @@
+use crate::bitset::BitSet;
+use crate::maze_grid::MazeGrid;
+use std::fmt::Write;
+
+/// Exporte la grille en JSON :
+/// `{"width":W,"height":H,"vertical_walls":[0,1,...],"horizontal_walls":[...]}`
+/// Les murs suivent la convention de `MazeGrid` (index y * width + x, 1 = mur)
+pub fn to_json(grid: &MazeGrid) -> String {
+    let mut json = String::with_capacity(64 + grid.width * grid.height * 4);
+    let _ = write!(json, r#"{{"width":{},"height":{},"vertical_walls":"#, grid.width, grid.height);
+    write_walls(&mut json, &grid.vertical_walls);
+    json.push_str(r#","horizontal_walls":"#);
+    write_walls(&mut json, &grid.horizontal_walls);
+    json.push('}');
+    json
+}
+
+fn write_walls(json: &mut String, walls: &BitSet) {
+    json.push('[');
+    for i in 0..walls.len() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push(if walls.get(i) { '1' } else { '0' });
+    }
+    json.push(']');
+}
@@ -0,0 +1,11 @@
+pub mod ascii;
+pub mod binary;
+pub mod json;
+pub mod pdf;
+pub mod svg;
+
+pub use ascii::{floor_to_ascii, to_ascii};
+pub use binary::{from_bytes, to_bytes, DecodeError};
+pub use json::to_json;
+pub use pdf::{export_layered_pdf, export_pdf, PaperSize, PdfOptions};
+pub use svg::to_svg;
@@ -0,0 +1,499 @@
+use crate::Point;
+use crate::error::MazeError;
+use crate::geometry::{MazeGeometry, Segment, StairMarker};
+use crate::maze_grid::MazeGrid;
+use crate::solver::solve;
+use crate::topology::LayeredGrid;
+use std::fmt::Write;
+
+/// Nombre de points PDF (1/72 de pouce) par millimètre
+const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+/// Hauteur réservée au titre en haut de chaque page (en points)
+const HEADER_HEIGHT: f64 = 28.0;
+
+/// Formats de papier supportés pour l'impression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaperSize {
+    A3,
+    A4,
+    A5,
+    Letter,
+    Legal,
+    Custom { width_mm: f64, height_mm: f64 },
+}
+
+impl PaperSize {
+    /// Dimensions (largeur, hauteur) en millimètres, en orientation portrait
+    pub fn dimensions_mm(&self) -> (f64, f64) {
+        match *self {
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::A5 => (148.0, 210.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::Legal => (215.9, 355.6),
+            PaperSize::Custom { width_mm, height_mm } => (width_mm, height_mm),
+        }
+    }
+}
+
+/// Options de mise en page de la fiche imprimable
+#[derive(Debug, Clone)]
+pub struct PdfOptions {
+    pub paper: PaperSize,
+    pub margin_mm: f64,
+    /// Titre affiché en haut de chaque page
+    pub title: String,
+    /// Cellule d'entrée (par défaut : coin supérieur gauche)
+    pub entrance: Option<Point>,
+    /// Cellule de sortie (par défaut : coin inférieur droit)
+    pub exit: Option<Point>,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::A4,
+            margin_mm: 15.0,
+            title: String::from("Labyrinthe"),
+            entrance: None,
+            exit: None,
+        }
+    }
+}
+
+/// Dimensions de la page et marge, en points
+struct Page {
+    width: f64,
+    height: f64,
+    margin: f64,
+}
+
+impl Page {
+    /// Erreur si le papier et les marges ne laissent pas de zone imprimable
+    fn new(options: &PdfOptions) -> Result<Self, MazeError> {
+        let (width_mm, height_mm) = options.paper.dimensions_mm();
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        let margin_fits = options.margin_mm.is_finite()
+            && options.margin_mm >= 0.0
+            && 2.0 * options.margin_mm < width_mm.min(height_mm);
+        if !positive(width_mm) || !positive(height_mm) || !margin_fits {
+            return Err(MazeError::InvalidPageSize);
+        }
+
+        Ok(Self {
+            width: width_mm * POINTS_PER_MM,
+            height: height_mm * POINTS_PER_MM,
+            margin: options.margin_mm * POINTS_PER_MM,
+        })
+    }
+
+    /// Mise à l'échelle pour que le labyrinthe tienne dans la zone imprimable
+    fn layout(&self, grid: &MazeGrid) -> Layout {
+        let available_width = (self.width - 2.0 * self.margin).max(1.0);
+        let available_height = (self.height - 2.0 * self.margin - HEADER_HEIGHT).max(1.0);
+        let scale = (available_width / grid.width as f64).min(available_height / grid.height as f64);
+        Layout {
+            origin_x: (self.width - grid.width as f64 * scale) / 2.0,
+            origin_y: self.height - self.margin - HEADER_HEIGHT,
+            scale,
+        }
+    }
+
+    /// Ordonnée de la ligne de base du titre
+    fn title_y(&self) -> f64 {
+        self.height - self.margin - 16.0
+    }
+}
+
+/// Transformation des unités de cellule vers les coordonnées de la page
+/// (l'axe y du PDF est orienté vers le haut)
+struct Layout {
+    origin_x: f64,
+    origin_y: f64,
+    scale: f64,
+}
+
+impl Layout {
+    fn x(&self, x: f64) -> f64 {
+        self.origin_x + x * self.scale
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        self.origin_y - y * self.scale
+    }
+}
+
+/// Génère un document PDF de deux pages :
+/// - Page 1 : le labyrinthe avec les marqueurs d'entrée et de sortie
+/// - Page 2 : le même labyrinthe avec la solution tracée
+///
+/// Erreur si l'entrée ou la sortie est hors de la grille, ou si le papier et les marges
+/// ne laissent pas de zone imprimable
+pub fn export_pdf(grid: &MazeGrid, options: &PdfOptions) -> Result<Vec<u8>, MazeError> {
+    let entrance = options.entrance.unwrap_or(Point { x: 0, y: 0 });
+    let exit = options.exit.unwrap_or(Point {
+        x: grid.width.saturating_sub(1),
+        y: grid.height.saturating_sub(1),
+    });
+    for point in [entrance, exit] {
+        grid.check_point(point.x, point.y)?;
+    }
+
+    let page = Page::new(options)?;
+    let layout = page.layout(grid);
+    let segments = grid.wall_segments();
+
+    // Page 1 : le labyrinthe seul
+    let mut maze_page = String::new();
+    write_title(&mut maze_page, &options.title, page.margin, page.title_y());
+    write_walls(&mut maze_page, &layout, &segments);
+    write_opening(&mut maze_page, grid, &layout, entrance);
+    write_opening(&mut maze_page, grid, &layout, exit);
+    write_marker(&mut maze_page, &layout, entrance, (0.13, 0.65, 0.33));
+    write_marker(&mut maze_page, &layout, exit, (0.86, 0.15, 0.15));
+
+    // Page 2 : le corrigé avec la solution
+    let mut solution_page = String::new();
+    let solution_title = format!("{} - Solution", options.title);
+    write_title(&mut solution_page, &solution_title, page.margin, page.title_y());
+    write_walls(&mut solution_page, &layout, &segments);
+    write_opening(&mut solution_page, grid, &layout, entrance);
+    write_opening(&mut solution_page, grid, &layout, exit);
+    if let Some(path) = solve(grid, entrance, exit) {
+        write_solution(&mut solution_page, grid, &layout, &path);
+    }
+    write_marker(&mut solution_page, &layout, entrance, (0.13, 0.65, 0.33));
+    write_marker(&mut solution_page, &layout, exit, (0.86, 0.15, 0.15));
+
+    Ok(build_document(page.width, page.height, &[maze_page, solution_page]))
+}
+
+/// Génère un document PDF d'une page par étage d'une grille 3D
+/// L'entrée est sur le premier étage, la sortie sur le dernier ; chaque escalier est
+/// un triangle pointant vers l'étage qu'il rejoint
+///
+/// Erreur si la grille n'a aucun étage, si l'entrée ou la sortie est hors de leur étage,
+/// ou si le papier et les marges ne laissent pas de zone imprimable
+pub fn export_layered_pdf(grid: &LayeredGrid, options: &PdfOptions) -> Result<Vec<u8>, MazeError> {
+    let last = grid.depth().saturating_sub(1);
+    let (Some(first_floor), Some(last_floor)) = (grid.floor(0), grid.floor(last)) else {
+        return Err(MazeError::InvalidDimensions { width: grid.width(), height: grid.height() });
+    };
+    let entrance = options.entrance.unwrap_or(Point { x: 0, y: 0 });
+    let exit = options.exit.unwrap_or(Point {
+        x: grid.width().saturating_sub(1),
+        y: grid.height().saturating_sub(1),
+    });
+    first_floor.check_point(entrance.x, entrance.y)?;
+    last_floor.check_point(exit.x, exit.y)?;
+
+    let page = Page::new(options)?;
+    let layout = page.layout(first_floor);
+
+    let mut pages = Vec::with_capacity(grid.depth());
+    for z in 0..grid.depth() {
+        let (Some(floor), Some(view)) = (grid.floor(z), grid.floor_view(z)) else {
+            continue;
+        };
+
+        let mut content = String::new();
+        let title = format!("{} - Étage {}/{}", options.title, z + 1, grid.depth());
+        write_title(&mut content, &title, page.margin, page.title_y());
+        write_walls(&mut content, &layout, &view.wall_segments());
+        write_stairs(&mut content, &layout, &view.stair_markers());
+        if z == 0 {
+            write_opening(&mut content, floor, &layout, entrance);
+            write_marker(&mut content, &layout, entrance, (0.13, 0.65, 0.33));
+        }
+        if z == last {
+            write_opening(&mut content, floor, &layout, exit);
+            write_marker(&mut content, &layout, exit, (0.86, 0.15, 0.15));
+        }
+        pages.push(content);
+    }
+
+    Ok(build_document(page.width, page.height, &pages))
+}
+
+/// Écrit le titre de la page en Helvetica
+fn write_title(content: &mut String, title: &str, x: f64, y: f64) {
+    let _ = writeln!(content, "BT /F1 14 Tf {:.2} {:.2} Td ({}) Tj ET", x, y, escape_text(title));
+}
+
+/// Trace tous les murs en un seul chemin
+fn write_walls(content: &mut String, layout: &Layout, segments: &[Segment]) {
+    let line_width = (layout.scale * 0.12).clamp(0.3, 2.0);
+    let _ = writeln!(content, "0 0 0 RG {:.3} w 1 J", line_width);
+    for segment in segments {
+        let _ = writeln!(
+            content,
+            "{:.2} {:.2} m {:.2} {:.2} l",
+            layout.x(segment.x1),
+            layout.y(segment.y1),
+            layout.x(segment.x2),
+            layout.y(segment.y2)
+        );
+    }
+    content.push_str("S\n");
+}
+
+/// Efface la bordure extérieure devant une cellule d'entrée/sortie située sur le bord
+fn write_opening(content: &mut String, grid: &MazeGrid, layout: &Layout, point: Point) {
+    let (x, y) = (point.x as f64, point.y as f64);
+    let edge = if point.x == 0 {
+        Some((x, y, x, y + 1.0))
+    } else if point.y == 0 {
+        Some((x, y, x + 1.0, y))
+    } else if point.x + 1 == grid.width {
+        Some((x + 1.0, y, x + 1.0, y + 1.0))
+    } else if point.y + 1 == grid.height {
+        Some((x, y + 1.0, x + 1.0, y + 1.0))
+    } else {
+        None
+    };
+
+    if let Some((x1, y1, x2, y2)) = edge {
+        // Le trait blanc est légèrement raccourci pour ne pas entamer les murs voisins
+        let inset = 0.15;
+        let (dx, dy) = ((x2 - x1) * inset, (y2 - y1) * inset);
+        let line_width = (layout.scale * 0.12).clamp(0.3, 2.0) * 1.5;
+        let _ = writeln!(
+            content,
+            "1 1 1 RG {:.3} w 0 J {:.2} {:.2} m {:.2} {:.2} l S",
+            line_width,
+            layout.x(x1 + dx),
+            layout.y(y1 + dy),
+            layout.x(x2 - dx),
+            layout.y(y2 - dy)
+        );
+    }
+}
+
+/// Dessine un carré plein au centre de la cellule
+fn write_marker(content: &mut String, layout: &Layout, point: Point, color: (f64, f64, f64)) {
+    let size = layout.scale * 0.5;
+    let _ = writeln!(
+        content,
+        "{:.3} {:.3} {:.3} rg {:.2} {:.2} {:.2} {:.2} re f",
+        color.0,
+        color.1,
+        color.2,
+        layout.x(point.x as f64 + 0.25),
+        layout.y(point.y as f64 + 0.75),
+        size,
+        size
+    );
+}
+
+/// Dessine les escaliers d'un étage en triangles pleins
+fn write_stairs(content: &mut String, layout: &Layout, markers: &[StairMarker]) {
+    if markers.is_empty() {
+        return;
+    }
+
+    content.push_str("0.486 0.227 0.929 rg\n");
+    for [(x1, y1), (x2, y2), (x3, y3)] in markers.iter().flat_map(StairMarker::triangles) {
+        let _ = writeln!(
+            content,
+            "{:.2} {:.2} m {:.2} {:.2} l {:.2} {:.2} l h",
+            layout.x(x1),
+            layout.y(y1),
+            layout.x(x2),
+            layout.y(y2),
+            layout.x(x3),
+            layout.y(y3)
+        );
+    }
+    content.push_str("f\n");
+}
+
+/// Trace la solution comme une polyligne passant par le centre des cellules
+fn write_solution(content: &mut String, grid: &MazeGrid, layout: &Layout, path: &[Point]) {
+    let line_width = (layout.scale * 0.3).clamp(0.5, 4.0);
+    let _ = writeln!(content, "0.23 0.51 0.96 RG {:.3} w 1 J 1 j", line_width);
+    for (i, point) in path.iter().enumerate() {
+        let (x, y) = grid.cell_center(grid.get_index(point.x, point.y));
+        // Un passage par un bord raccordé (mode torique) interrompt le tracé, pas un tunnel
+        let jumps = i > 0
+            && path[i - 1].x.abs_diff(point.x) + path[i - 1].y.abs_diff(point.y) > 1
+            && !grid.has_tunnel(path[i - 1], *point);
+        let operator = if i == 0 || jumps { "m" } else { "l" };
+        let _ = writeln!(content, "{:.2} {:.2} {}", layout.x(x), layout.y(y), operator);
+    }
+    content.push_str("S\n");
+}
+
+/// Échappe une chaîne pour un littéral PDF, encodée en WinAnsi
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            // Caractères Latin-1 : encodés en octal pour rester en ASCII dans le fichier
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(escaped, "\\{:03o}", c as u32);
+            }
+            '–' => escaped.push_str("\\226"),
+            '—' => escaped.push_str("\\227"),
+            '’' => escaped.push_str("\\222"),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+/// Assemble les objets PDF et la table de références croisées
+fn build_document(page_width: f64, page_height: f64, pages: &[String]) -> Vec<u8> {
+    // Objets : 1 = catalogue, 2 = arbre des pages, 3 = police, puis (page, contenu) par page
+    let mut objects = Vec::new();
+    objects.push(String::from("<< /Type /Catalog /Pages 2 0 R >>"));
+
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 4 + i * 2)).collect();
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+    objects.push(String::from(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    ));
+
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            page_width,
+            page_height,
+            5 + i * 2
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut document = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(document.len());
+        let _ = write!(document, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+
+    let xref_offset = document.len();
+    let _ = write!(document, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(document, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        document,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    );
+
+    document.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+
+    #[test]
+    fn document_structure_is_consistent() {
+        let mut grid = MazeGrid::new(3, 2);
+        grid.clear_grid();
+        let bytes = export_pdf(&grid, &PdfOptions { title: String::from("Essai (é)"), ..PdfOptions::default() }).unwrap();
+        let document = String::from_utf8(bytes).expect("le PDF doit rester en ASCII");
+        assert!(document.is_ascii());
+        assert!(document.starts_with("%PDF-1.4\n"));
+        assert!(document.contains("(Essai \\(\\351\\)) Tj"));
+
+        // Fin de fichier : startxref pointe sur la table de références croisées
+        let trailer_start = document.rfind("trailer\n").unwrap();
+        let trailer = &document[trailer_start..];
+        let xref_offset: usize = trailer.lines().nth(3).unwrap().parse().unwrap();
+        assert!(trailer.ends_with(&format!("startxref\n{}\n%%EOF\n", xref_offset)));
+
+        // Catalogue, arbre des pages, police, puis (page, contenu) pour les deux pages
+        let object_count = 7;
+        assert_eq!(trailer.lines().nth(1), Some(format!("<< /Size {} /Root 1 0 R >>", object_count + 1).as_str()));
+
+        let xref: Vec<&str> = document[xref_offset..trailer_start].lines().collect();
+        assert_eq!(xref[0], "xref");
+        assert_eq!(xref[1], format!("0 {}", object_count + 1));
+        assert_eq!(xref[2], "0000000000 65535 f ");
+        assert_eq!(xref.len(), 3 + object_count);
+
+        // Chaque entrée fait 20 octets et pointe sur le début de son objet
+        for (i, entry) in xref[3..].iter().enumerate() {
+            assert_eq!(entry.len() + 1, 20);
+            assert!(entry.ends_with(" 00000 n "));
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(document[offset..].starts_with(&format!("{} 0 obj\n", i + 1)), "objet {}", i + 1);
+        }
+
+        // Longueur déclarée de chaque flux de contenu
+        for object in [5, 7] {
+            let start = document.find(&format!("{} 0 obj\n<< /Length ", object)).unwrap();
+            let header = &document[start..];
+            let length: usize = header.split_whitespace().nth(5).unwrap().parse().unwrap();
+            let stream = &header[header.find("stream\n").unwrap() + 7..];
+            assert!(stream[length..].starts_with("endstream\nendobj\n"));
+        }
+    }
+
+    #[test]
+    fn rejects_markers_outside_the_grid() {
+        let grid = MazeGrid::new(3, 2);
+        let options = PdfOptions { exit: Some(Point::new(3, 1)), ..PdfOptions::default() };
+        assert_eq!(export_pdf(&grid, &options).err(), Some(MazeError::OutOfBounds { x: 3, y: 1 }));
+        let options = PdfOptions { entrance: Some(Point::new(0, 2)), ..PdfOptions::default() };
+        assert_eq!(export_pdf(&grid, &options).err(), Some(MazeError::OutOfBounds { x: 0, y: 2 }));
+    }
+
+    #[test]
+    fn rejects_unusable_page_sizes() {
+        let grid = MazeGrid::new(3, 2);
+        let custom = |width_mm, height_mm, margin_mm| PdfOptions {
+            paper: PaperSize::Custom { width_mm, height_mm },
+            margin_mm,
+            ..PdfOptions::default()
+        };
+
+        for options in [
+            custom(0.0, 100.0, 5.0),
+            custom(100.0, -1.0, 5.0),
+            custom(f64::NAN, 100.0, 5.0),
+            custom(100.0, f64::INFINITY, 5.0),
+            custom(100.0, 100.0, -1.0),
+            custom(100.0, 100.0, f64::NAN),
+            custom(100.0, 100.0, 50.0),
+        ] {
+            assert_eq!(export_pdf(&grid, &options).err(), Some(MazeError::InvalidPageSize), "{:?}", options.paper);
+        }
+        assert!(export_pdf(&grid, &custom(100.0, 100.0, 0.0)).is_ok());
+    }
+
+    #[test]
+    fn layered_grids_get_one_page_per_floor_with_stairs() {
+        let mut grid = LayeredGrid::new(2, 1, 3);
+        grid.fill_walls();
+        grid.link(1, 3);
+        let options = PdfOptions { title: String::from("Tour"), ..PdfOptions::default() };
+        let document = String::from_utf8(export_layered_pdf(&grid, &options).unwrap()).unwrap();
+
+        assert!(document.contains("/Count 3"));
+        for floor in 1..=3 {
+            assert!(document.contains(&format!("(Tour - \\311tage {}/3) Tj", floor)));
+        }
+        // Un escalier montant sur l'étage 1, descendant sur l'étage 2, aucun sur l'étage 3
+        assert_eq!(document.matches("0.486 0.227 0.929 rg").count(), 2);
+
+        // Entrée sur le premier étage, sortie sur le dernier
+        let exit = PdfOptions { exit: Some(Point::new(2, 0)), ..PdfOptions::default() };
+        assert_eq!(export_layered_pdf(&grid, &exit).err(), Some(MazeError::OutOfBounds { x: 2, y: 0 }));
+        let empty = LayeredGrid::new(2, 1, 0);
+        assert_eq!(
+            export_layered_pdf(&empty, &options).err(),
+            Some(MazeError::InvalidDimensions { width: 2, height: 1 })
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::geometry::{MazeGeometry, StairMarker};
+use std::fmt::Write;
+
+/// Exporte le labyrinthe en SVG : un seul chemin contenant tous les murs
+/// Fonctionne pour toute grille fournissant sa géométrie (carrée, hexagonale...)
+/// Les murs courbes des grilles polaires sont tracés avec des commandes d'arc
+/// Les escaliers des étages d'une grille 3D sont des triangles pleins au centre des cellules
+/// `cell_size` est la taille d'une cellule en pixels
+pub fn to_svg(grid: &impl MazeGeometry, cell_size: f64) -> String {
+    let margin = cell_size / 2.0;
+    let (grid_width, grid_height) = grid.size();
+    let width = grid_width * cell_size + 2.0 * margin;
+    let height = grid_height * cell_size + 2.0 * margin;
+    let stroke_width = (cell_size * 0.15).max(1.0);
+
+    let mut path = String::new();
+    for segment in grid.wall_segments() {
+        let _ = write!(
+            path,
+            "M{:.2} {:.2}L{:.2} {:.2}",
+            margin + segment.x1 * cell_size,
+            margin + segment.y1 * cell_size,
+            margin + segment.x2 * cell_size,
+            margin + segment.y2 * cell_size
+        );
+    }
+
+    for arc in grid.wall_arcs() {
+        let (x1, y1) = arc.point_at(arc.start_angle);
+        let (x2, y2) = arc.point_at(arc.end_angle);
+        let large_arc = u8::from(arc.end_angle - arc.start_angle > std::f64::consts::PI);
+        let _ = write!(
+            path,
+            "M{:.2} {:.2}A{:.2} {:.2} 0 {} 1 {:.2} {:.2}",
+            margin + x1 * cell_size,
+            margin + y1 * cell_size,
+            arc.radius * cell_size,
+            arc.radius * cell_size,
+            large_arc,
+            margin + x2 * cell_size,
+            margin + y2 * cell_size
+        );
+    }
+
+    let mut stairs = String::new();
+    for triangle in grid.stair_markers().iter().flat_map(StairMarker::triangles) {
+        let [(x1, y1), (x2, y2), (x3, y3)] = triangle.map(|(x, y)| (margin + x * cell_size, margin + y * cell_size));
+        let _ = write!(stairs, "M{:.2} {:.2}L{:.2} {:.2}L{:.2} {:.2}Z", x1, y1, x2, y2, x3, y3);
+    }
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    );
+    let _ = writeln!(svg, r##"<rect width="100%" height="100%" fill="#ffffff"/>"##);
+    let _ = writeln!(
+        svg,
+        r##"<path d="{}" stroke="#1a1a1a" stroke-width="{}" stroke-linecap="square" fill="none"/>"##,
+        path, stroke_width
+    );
+    if !stairs.is_empty() {
+        let _ = writeln!(svg, r##"<path d="{}" fill="#7c3aed"/>"##, stairs);
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_grid::MazeGrid;
+    use crate::topology::{LayeredGrid, Topology};
+
+    #[test]
+    fn floors_show_their_stairs() {
+        let mut grid = LayeredGrid::new(2, 1, 2);
+        grid.fill_walls();
+        grid.link(1, 3);
+
+        // Un triangle par escalier, décalé de la marge d'une demi-cellule
+        let lower = to_svg(&grid.floor_view(0).unwrap(), 10.0);
+        assert!(lower.contains(r##"<path d="M20.00 7.50L22.50 12.50L17.50 12.50Z" fill="#7c3aed"/>"##), "{}", lower);
+        let upper = to_svg(&grid.floor_view(1).unwrap(), 10.0);
+        assert!(upper.contains(r##"<path d="M20.00 12.50L17.50 7.50L22.50 7.50Z" fill="#7c3aed"/>"##), "{}", upper);
+
+        // Sans escalier, aucun chemin de marqueurs
+        assert!(!to_svg(&MazeGrid::new(2, 1), 10.0).contains("#7c3aed"));
+    }
+}
@@ -1,107 +1,139 @@
-use super::{GenerationAlgorithm, GenerationResult, WallChange, WallType};
-use crate::maze_grid::MazeGrid;
+use super::{GenerationAlgorithm, GenerationResult, MazeRng, ratio};
 use crate::Point;
+use crate::codec::{Reader, write_bools, write_cells, write_option, write_varint};
+use crate::export::DecodeError;
+use crate::topology::Topology;
 use rand::Rng;
 
 #[derive(Default)]
 pub struct BacktrackingGenerator {
+    rng: MazeRng,
     visited: Vec<bool>,
-    stack: Vec<Point>,
-    current_point: Option<Point>,
+    stack: Vec<usize>,
+    // Cellules retirées de la pile : chacune y entre puis en sort une fois
+    backtracked: usize,
+    current_cell: Option<usize>,
     is_finished: bool,
-    width: usize,
-    height: usize,
+    // Labyrinthe tissé : autoriser les tunnels sous les couloirs existants
+    weave: bool,
 }
 
 impl BacktrackingGenerator {
-    fn get_random_unvisited_neighbors(&self, grid: &MazeGrid, point: Point) -> Option<Point> {
-        let candidates = [
-            (point.x, point.y + 1),
-            (point.x, point.y - 1),
-            (point.x + 1, point.y),
-            (point.x - 1, point.y),
-        ];
-
-        let mut rng = rand::rng();
-        let start = rng.random_range(0..4);
-
-        for i in 0..4 {
-            let (dx, dy) = candidates[(start + i) % 4];
-            if grid.is_valid_point(dx, dy) {
-                let index = grid.get_index(dx, dy);
-                if !self.visited[index] {
-                    return Some(Point { x: dx, y: dy });
-                }
+    /// Créer un générateur reproductible à partir d'une graine
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: MazeRng::from_seed(seed), ..Self::default() }
+    }
+
+    /// Activer le tissage : le parcours peut passer sous un couloir déjà creusé
+    pub fn weave(mut self, enabled: bool) -> Self {
+        self.weave = enabled;
+        self
+    }
+
+    // Retourne un voisin non visité, et vrai s'il faut l'atteindre par un tunnel
+    fn get_random_unvisited_neighbors<G: Topology>(&mut self, grid: &G, cell: usize) -> Option<(usize, bool)> {
+        let mut candidates: Vec<(usize, bool)> = grid.neighbors(cell).into_iter().map(|n| (n, false)).collect();
+        if self.weave {
+            candidates.extend(grid.tunnel_candidates(cell).into_iter().map(|n| (n, true)));
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+
+        // Parcourir les voisins à partir d'un point de départ aléatoire
+        let start = self.rng.random_range(0..candidates.len());
+
+        for i in 0..candidates.len() {
+            let candidate = candidates[(start + i) % candidates.len()];
+            if !self.visited[candidate.0] {
+                return Some(candidate);
             }
         }
         None
     }
 
-    fn remove_wall_between_with_tracking(&self, grid: &mut MazeGrid, from: Point, to: Point) -> Vec<WallChange> {
-        let mut changes = Vec::new();
-        
-        if from.x == to.x {
-            // Mouvement vertical
-            let y_min = if from.y < to.y { from.y } else { to.y };
-            grid.remove_horizontal_wall(from.x, y_min);
-            changes.push(WallChange {
-                x: from.x,
-                y: y_min,
-                wall_type: WallType::Horizontal,
-            });
-        } else if from.y == to.y {
-            // Mouvement horizontal
-            let x_min = if from.x < to.x { from.x } else { to.x };
-            grid.remove_vertical_wall(x_min, from.y);
-            changes.push(WallChange {
-                x: x_min,
-                y: from.y,
-                wall_type: WallType::Vertical,
-            });
+    /// État complet, pour un instantané (`MazeGenerator::snapshot`)
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) {
+        self.rng.save(bytes);
+        write_bools(bytes, &self.visited);
+        write_cells(bytes, &self.stack);
+        write_varint(bytes, self.backtracked);
+        write_option(bytes, self.current_cell);
+        bytes.extend([u8::from(self.is_finished), u8::from(self.weave)]);
+    }
+
+    /// Relit `save` pour une grille de `cell_count` cellules
+    pub(crate) fn load(reader: &mut Reader, cell_count: usize) -> Result<Self, DecodeError> {
+        let rng = MazeRng::load(reader)?;
+        let visited = reader.bools(cell_count)?;
+        let stack = reader.cells(cell_count)?;
+
+        // Seule une cellule visitée peut avoir été retirée de la pile
+        let backtracked = reader.varint()?;
+        if backtracked > visited.iter().filter(|&&visited| visited).count() {
+            return Err(DecodeError::InvalidState);
         }
-        
-        changes
+
+        Ok(Self {
+            rng,
+            visited,
+            stack,
+            backtracked,
+            current_cell: reader.option(cell_count)?,
+            is_finished: reader.bool()?,
+            weave: reader.bool()?,
+        })
     }
 }
 
-impl GenerationAlgorithm for BacktrackingGenerator {
-    fn start(&mut self, grid: &mut MazeGrid) {
-        self.width = grid.width;
-        self.height = grid.height;
-        self.visited = vec![false; self.width * self.height];
+impl<G: Topology> GenerationAlgorithm<G> for BacktrackingGenerator {
+    fn start(&mut self, grid: &mut G) {
+        self.visited = vec![false; grid.cell_count()];
         self.stack = Vec::new();
+        self.backtracked = 0;
         self.is_finished = false;
+        grid.fill_walls();
+
+        // Topologie vide : rien à creuser
+        if grid.cell_count() == 0 {
+            self.current_cell = None;
+            self.is_finished = true;
+            return;
+        }
 
-        // Commencer depuis le coin supérieur gauche
-        let start_point = Point { x: 0, y: 0 };
-        self.stack.push(start_point);
-        self.current_point = Some(start_point);
-        grid.fill_grid();
+        // Commencer depuis la première cellule (coin supérieur gauche pour la grille carrée)
+        let start_cell = 0;
+        self.stack.push(start_cell);
+        self.current_cell = Some(start_cell);
     }
 
-    fn step(&mut self, grid: &mut MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+    fn step(&mut self, grid: &mut G) -> (GenerationResult, Vec<G::Change>) {
         // Si pas de point courant, on a fini
         let Some(current) = self.stack.last().copied() else {
             self.is_finished = true;
-            self.current_point = None;
+            self.current_cell = None;
             return (GenerationResult::Finished, Vec::new());
         };
 
-        self.current_point = Some(current);
+        self.current_cell = Some(current);
 
         // Marquer le point courant comme visité
-        let current_index = grid.get_index(current.x, current.y);
-        self.visited[current_index] = true;
+        self.visited[current] = true;
 
         // Chercher un voisin non visité
-        if let Some(next) = self.get_random_unvisited_neighbors(grid, current) {
-            // On a trouvé un voisin, supprimer le mur et avancer
-            let wall_changes = self.remove_wall_between_with_tracking(grid, current, next);
+        if let Some((next, through_tunnel)) = self.get_random_unvisited_neighbors(grid, current) {
+            // On a trouvé un voisin, supprimer le mur (ou creuser le tunnel) et avancer
+            let wall_changes = if through_tunnel {
+                grid.tunnel(current, next)
+            } else {
+                grid.link(current, next).into_iter().collect()
+            };
             self.stack.push(next);
             (GenerationResult::Continue, wall_changes)
         } else {
             // Pas de voisin non visité, revenir en arrière
             self.stack.pop();
+            self.backtracked += 1;
             (GenerationResult::Continue, Vec::new())
         }
     }
@@ -114,8 +146,56 @@ impl GenerationAlgorithm for BacktrackingGenerator {
         "Recursive Backtracking"
     }
 
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    // Entrées et sorties de pile effectuées, sur les 2n de la génération complète
+    fn progress(&self) -> f64 {
+        if self.is_finished { 1.0 } else { ratio(2 * self.backtracked + self.stack.len(), 2 * self.visited.len()) }
+    }
+
+    fn get_cell_layers(&self, grid: &G) -> Vec<Vec<Point>> {
         // Layer 0 : La pile (chemin de backtracking)
-        vec![self.stack.clone()]
+        vec![self.stack.iter().map(|&cell| grid.cell_point(cell)).collect()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_grid::MazeGrid;
+
+    fn reload(generator: &BacktrackingGenerator, cell_count: usize) -> Result<BacktrackingGenerator, DecodeError> {
+        let mut bytes = Vec::new();
+        generator.save(&mut bytes);
+        BacktrackingGenerator::load(&mut Reader::new(&bytes), cell_count)
+    }
+
+    #[test]
+    fn load_round_trips_state() {
+        let mut grid = MazeGrid::new(4, 3);
+        let mut generator = BacktrackingGenerator::with_seed(5);
+        GenerationAlgorithm::<MazeGrid>::start(&mut generator, &mut grid);
+        while generator.backtracked == 0 {
+            generator.step(&mut grid);
+        }
+
+        let loaded = reload(&generator, 12).unwrap();
+        assert_eq!(loaded.visited, generator.visited);
+        assert_eq!(loaded.stack, generator.stack);
+        assert_eq!(loaded.backtracked, generator.backtracked);
+        assert_eq!(GenerationAlgorithm::<MazeGrid>::progress(&loaded), GenerationAlgorithm::<MazeGrid>::progress(&generator));
+    }
+
+    #[test]
+    fn load_rejects_more_backtracks_than_visited_cells() {
+        let mut grid = MazeGrid::new(4, 3);
+        let mut generator = BacktrackingGenerator::with_seed(5);
+        GenerationAlgorithm::<MazeGrid>::start(&mut generator, &mut grid);
+        generator.step(&mut grid);
+
+        generator.backtracked = usize::MAX;
+        assert_eq!(reload(&generator, 12).err(), Some(DecodeError::InvalidState));
+        generator.backtracked = 2;
+        assert_eq!(reload(&generator, 12).err(), Some(DecodeError::InvalidState));
+        generator.backtracked = 1;
+        assert!(reload(&generator, 12).is_ok());
     }
 }
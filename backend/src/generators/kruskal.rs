@@ -1,16 +1,22 @@
-use super::{GenerationAlgorithm, GenerationResult, WallChange, WallType};
-use crate::maze_grid::MazeGrid;
+use super::{GenerationAlgorithm, GenerationResult, MazeRng, ratio};
 use crate::Point;
-use rand::seq::SliceRandom;
+use crate::codec::{Reader, write_option, write_varint};
+use crate::export::DecodeError;
+use crate::topology::Topology;
+use rand::Rng;
+use rand::seq::{IndexedRandom, SliceRandom};
 
+// Mur entre deux cellules adjacentes
 struct WallEntry {
-    x: usize,
-    y: usize,
-    wall_type: WallType,
+    cell1: usize,
+    cell2: usize,
 }
 
 #[derive(Default)]
 pub struct KruskalGenerator {
+    // Générateur aléatoire (graine fixée pour une génération reproductible)
+    rng: MazeRng,
+
     // Union-Find pour tracker les ensembles de cellules connectées
     // parent[i] = parent de la cellule i (-1 si racine)
     parent: Vec<isize>,
@@ -25,17 +31,89 @@ pub struct KruskalGenerator {
     current_wall_index: usize,
 
     // Position courante pour l'animation
-    current_point: Option<Point>,
+    current_cell: Option<usize>,
 
     // État de fin
     is_finished: bool,
 
-    // Dimensions
-    width: usize,
-    height: usize,
+    // Labyrinthe tissé : creuser aussi des tunnels sous les couloirs existants
+    weave: bool,
 }
 
 impl KruskalGenerator {
+    /// Créer un générateur reproductible à partir d'une graine
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: MazeRng::from_seed(seed), ..Self::default() }
+    }
+
+    /// Activer le tissage : des tunnels passent sous les couloirs déjà creusés
+    pub fn weave(mut self, enabled: bool) -> Self {
+        self.weave = enabled;
+        self
+    }
+
+    // Tenter un tunnel depuis la cellule vers un ensemble différent
+    fn try_tunnel<G: Topology>(&mut self, grid: &mut G, cell: usize) -> Option<Vec<G::Change>> {
+        let root = self.find(cell);
+        let candidates: Vec<usize> = grid
+            .tunnel_candidates(cell)
+            .into_iter()
+            .filter(|&other| self.find(other) != root)
+            .collect();
+        let target = *candidates.choose(&mut self.rng)?;
+
+        let wall_changes = grid.tunnel(cell, target);
+        if wall_changes.is_empty() {
+            return None;
+        }
+
+        self.union(cell, target);
+        Some(wall_changes)
+    }
+
+    /// État complet, pour un instantané (`MazeGenerator::snapshot`)
+    /// L'Union-Find n'est pas sauvegardé : ses ensembles sont les parties connexes de la grille
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) {
+        self.rng.save(bytes);
+        write_varint(bytes, self.walls.len());
+        for wall in &self.walls {
+            write_varint(bytes, wall.cell1);
+            write_varint(bytes, wall.cell2);
+        }
+        write_varint(bytes, self.current_wall_index);
+        write_option(bytes, self.current_cell);
+        bytes.extend([u8::from(self.is_finished), u8::from(self.weave)]);
+    }
+
+    /// Relit `save`, puis reconstruit l'Union-Find à partir des passages déjà creusés dans la grille
+    pub(crate) fn load<G: Topology>(reader: &mut Reader, grid: &G) -> Result<Self, DecodeError> {
+        let cell_count = grid.cell_count();
+        let rng = MazeRng::load(reader)?;
+        let walls: Vec<WallEntry> = (0..reader.list_len()?)
+            .map(|_| Ok(WallEntry { cell1: reader.cell(cell_count)?, cell2: reader.cell(cell_count)? }))
+            .collect::<Result<_, DecodeError>>()?;
+        let current_wall_index = reader.varint()?;
+        if current_wall_index > walls.len() {
+            return Err(DecodeError::InvalidState);
+        }
+
+        let mut generator = Self {
+            rng,
+            parent: vec![-1; cell_count],
+            rank: vec![0; cell_count],
+            walls,
+            current_wall_index,
+            current_cell: reader.option(cell_count)?,
+            is_finished: reader.bool()?,
+            weave: reader.bool()?,
+        };
+        for cell in 0..cell_count {
+            for neighbor in grid.passages(cell) {
+                generator.union(cell, neighbor);
+            }
+        }
+        Ok(generator)
+    }
 
     // Trouver la racine de l'ensemble contenant la cellule (avec compression de chemin)
     fn find(&mut self, cell: usize) -> usize {
@@ -72,104 +150,72 @@ impl KruskalGenerator {
     }
 }
 
-impl GenerationAlgorithm for KruskalGenerator {
-    fn start(&mut self, grid: &mut MazeGrid) {
-        self.width = grid.width;
-        self.height = grid.height;
-        let total_cells = self.width * self.height;
+impl<G: Topology> GenerationAlgorithm<G> for KruskalGenerator {
+    fn start(&mut self, grid: &mut G) {
+        let total_cells = grid.cell_count();
 
         // Initialiser Union-Find : chaque cellule est sa propre racine
         self.parent = vec![-1; total_cells];
         self.rank = vec![0; total_cells];
 
         // Remplir la grille de murs
-        grid.fill_grid();
+        grid.fill_walls();
 
-        // Créer la liste de tous les murs possibles
+        // Créer la liste de tous les murs possibles (chaque paire de voisins une seule fois)
         self.walls = Vec::new();
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                // Mur vertical (à droite)
-                if x < self.width - 1 {
-                    self.walls.push(WallEntry {
-                        x,
-                        y,
-                        wall_type: WallType::Vertical,
-                    });
-                }
-
-                // Mur horizontal (en bas)
-                if y < self.height - 1 {
-                    self.walls.push(WallEntry {
-                        x,
-                        y,
-                        wall_type: WallType::Horizontal,
-                    });
+        for cell1 in 0..total_cells {
+            for cell2 in grid.neighbors(cell1) {
+                if cell1 < cell2 {
+                    self.walls.push(WallEntry { cell1, cell2 });
                 }
             }
         }
 
         // Mélanger aléatoirement la liste des murs
-        let mut rng = rand::rng();
-        self.walls.shuffle(&mut rng);
+        self.walls.shuffle(&mut self.rng);
 
         self.current_wall_index = 0;
-        self.is_finished = false;
-        self.current_point = Some(Point { x: 0, y: 0 });
+        // Topologie vide : rien à creuser
+        self.is_finished = total_cells == 0;
+        self.current_cell = (total_cells > 0).then_some(0);
     }
 
-    fn step(&mut self, grid: &mut MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+    fn step(&mut self, grid: &mut G) -> (GenerationResult, Vec<G::Change>) {
         // Si tous les murs ont été traités, terminé
         if self.current_wall_index >= self.walls.len() {
             self.is_finished = true;
-            self.current_point = None;
+            self.current_cell = None;
             return (GenerationResult::Finished, Vec::new());
         }
 
-        // Prendre le mur courant et copier ses valeurs
-        let wall_x = self.walls[self.current_wall_index].x;
-        let wall_y = self.walls[self.current_wall_index].y;
-        let wall_type = self.walls[self.current_wall_index].wall_type;
+        // Prendre le mur courant : les deux cellules de part et d'autre
+        let cell1 = self.walls[self.current_wall_index].cell1;
+        let cell2 = self.walls[self.current_wall_index].cell2;
         self.current_wall_index += 1;
 
-        // Déterminer les deux cellules de part et d'autre du mur
-        let (cell1_x, cell1_y, cell2_x, cell2_y) = match wall_type {
-            WallType::Vertical => {
-                // Mur vertical à droite de (x, y)
-                (wall_x, wall_y, wall_x + 1, wall_y)
-            }
-            WallType::Horizontal => {
-                // Mur horizontal en bas de (x, y)
-                (wall_x, wall_y, wall_x, wall_y + 1)
-            }
-        };
-
-        let cell1_index = grid.get_index(cell1_x, cell1_y);
-        let cell2_index = grid.get_index(cell2_x, cell2_y);
-
         // Mettre à jour la position courante pour l'animation
-        self.current_point = Some(Point { x: cell1_x, y: cell1_y });
+        self.current_cell = Some(cell1);
+
+        // Labyrinthe tissé : une fois sur deux, tenter d'abord un tunnel depuis cette cellule
+        // Le mur courant sera traité à l'étape suivante
+        if self.weave
+            && self.rng.random_bool(0.5)
+            && let Some(wall_changes) = self.try_tunnel(grid, cell1)
+        {
+            self.current_wall_index -= 1;
+            return (GenerationResult::Continue, wall_changes);
+        }
 
         // Vérifier si les deux cellules sont dans des ensembles différents
-        if self.find(cell1_index) != self.find(cell2_index) {
-            // Elles sont dans des ensembles différents, on peut retirer le mur
-
-            // Unir les ensembles
-            self.union(cell1_index, cell2_index);
-
-            // Supprimer le mur
-            match wall_type {
-                WallType::Vertical => grid.remove_vertical_wall(wall_x, wall_y),
-                WallType::Horizontal => grid.remove_horizontal_wall(wall_x, wall_y),
+        if self.find(cell1) != self.find(cell2) {
+            // Elles sont dans des ensembles différents : supprimer le mur s'il peut l'être
+            // (un croisement de labyrinthe tissé refuse les passages dans l'axe de son tunnel)
+            if let Some(wall_change) = grid.link(cell1, cell2) {
+                // Unir les ensembles et retourner le changement
+                self.union(cell1, cell2);
+                return (GenerationResult::Continue, vec![wall_change]);
             }
-
-            // Retourner le changement
-            return (GenerationResult::Continue, vec![WallChange {
-                x: wall_x,
-                y: wall_y,
-                wall_type,
-            }]);
         }
 
         // Les cellules sont déjà connectées, ne pas retirer le mur
@@ -184,10 +230,15 @@ impl GenerationAlgorithm for KruskalGenerator {
         "Kruskal's Algorithm"
     }
 
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    // Murs traités sur l'ensemble des murs mélangés
+    fn progress(&self) -> f64 {
+        if self.is_finished { 1.0 } else { ratio(self.current_wall_index, self.walls.len()) }
+    }
+
+    fn get_cell_layers(&self, grid: &G) -> Vec<Vec<Point>> {
         // Layer 0 : La cellule courante
-        if let Some(point) = self.current_point {
-            vec![vec![point]]
+        if let Some(cell) = self.current_cell {
+            vec![vec![grid.cell_point(cell)]]
         } else {
             vec![]
         }
@@ -1,5 +1,13 @@
 use crate::Point;
+use crate::codec::Reader;
+use crate::error::MazeError;
+use crate::export::DecodeError;
 use crate::maze_grid::MazeGrid;
+use crate::topology::Topology;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::str::FromStr;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
 pub mod backtracking;
@@ -14,32 +22,76 @@ pub use kruskal::KruskalGenerator;
 pub use wilson::WilsonGenerator;
 pub use recursive_division::RecursiveDivisionGenerator;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GenerationResult {
     Continue,
     Finished,
 }
 
-#[wasm_bindgen]
-#[derive(Debug, Clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct WallChange {
     pub x: usize,
     pub y: usize,
     pub wall_type: WallType,
+    pub op: WallOp,
 }
 
-#[wasm_bindgen]
-#[derive(Debug, Clone, Copy)]
+impl WallChange {
+    // Changement qui annule celui-ci
+    pub fn inverse(self) -> WallChange {
+        WallChange { op: self.op.inverse(), ..self }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WallType {
     Vertical = 0,
     Horizontal = 1,
 }
 
+// Cellule entrée dans un layer d'animation (entered = true) ou sortie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerDelta {
+    pub layer: usize,
+    pub point: Point,
+    pub entered: bool,
+}
+
+// Sens d'un changement de mur : ajouté ou supprimé
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallOp {
+    Added = 0,
+    Removed = 1,
+}
+
+impl WallOp {
+    // Opération qui mène à l'état `present`
+    pub fn from_present(present: bool) -> WallOp {
+        if present { WallOp::Added } else { WallOp::Removed }
+    }
+
+    // Vrai si le mur est présent après l'opération
+    pub fn is_added(self) -> bool {
+        self == WallOp::Added
+    }
+
+    // Opération inverse (annulation)
+    pub fn inverse(self) -> WallOp {
+        match self {
+            WallOp::Added => WallOp::Removed,
+            WallOp::Removed => WallOp::Added,
+        }
+    }
+}
+
 // Note: Vec<WallChange> n'est pas supporté par wasm-bindgen
-// On va gérer cela différemment dans lib.rs
+// On va gérer cela différemment dans wasm.rs
 
 // Enum pour identifier les types de générateurs
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AlgorithmKind {
     Backtracking,
@@ -50,20 +102,101 @@ pub enum AlgorithmKind {
 }
 
 
+impl AlgorithmKind {
+    pub const ALL: [AlgorithmKind; 5] = [
+        AlgorithmKind::Backtracking,
+        AlgorithmKind::Prim,
+        AlgorithmKind::Kruskal,
+        AlgorithmKind::Wilson,
+        AlgorithmKind::RecursiveDivision,
+    ];
+}
+
+impl FromStr for AlgorithmKind {
+    type Err = String;
+
+    /// Accepte les noms courts utilisés en ligne de commande (insensible à la casse)
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "backtracking" | "dfs" => Ok(AlgorithmKind::Backtracking),
+            "prim" => Ok(AlgorithmKind::Prim),
+            "kruskal" => Ok(AlgorithmKind::Kruskal),
+            "wilson" => Ok(AlgorithmKind::Wilson),
+            "division" | "recursive-division" => Ok(AlgorithmKind::RecursiveDivision),
+            _ => Err(format!("Algorithme inconnu : {}", name)),
+        }
+    }
+}
+
+/// Générateur pseudo-aléatoire partagé par tous les algorithmes
+/// Initialisé depuis l'entropie du système, ou depuis une graine pour une génération reproductible
+#[derive(Clone)]
+pub struct MazeRng(ChaCha8Rng);
+
+impl MazeRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Position exacte dans la suite aléatoire : graine ChaCha (32 octets), flux (8) et mot courant (16)
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.0.get_seed());
+        bytes.extend_from_slice(&self.0.get_stream().to_le_bytes());
+        bytes.extend_from_slice(&self.0.get_word_pos().to_le_bytes());
+    }
+
+    /// Relit `save` : la suite reprend au même endroit
+    pub(crate) fn load(reader: &mut Reader) -> Result<Self, DecodeError> {
+        let mut seed = [0; 32];
+        seed.copy_from_slice(reader.take(32)?);
+        let mut stream = [0; 8];
+        stream.copy_from_slice(reader.take(8)?);
+        let mut word_pos = [0; 16];
+        word_pos.copy_from_slice(reader.take(16)?);
+
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        rng.set_stream(u64::from_le_bytes(stream));
+        rng.set_word_pos(u128::from_le_bytes(word_pos));
+        Ok(Self(rng))
+    }
+}
+
+impl Default for MazeRng {
+    fn default() -> Self {
+        Self(ChaCha8Rng::from_os_rng())
+    }
+}
+
+impl RngCore for MazeRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+}
+
 // Possibiliter d'utiliser enum_dispatch qui fait le dispatch automatiquement, mais on garde explicite pour l'instant
 // Enum contenant les générateurs avec dispatch intégré
+// Custom permet de brancher un générateur défini hors de la crate
 pub enum Algorithm {
     Backtracking(BacktrackingGenerator),
     Prim(PrimGenerator),
     Kruskal(KruskalGenerator),
     Wilson(WilsonGenerator),
     RecursiveDivision(RecursiveDivisionGenerator),
+    Custom(Box<dyn GenerationAlgorithm>),
 }
 
 impl Algorithm {
     
     /// Créer un nouvel algorithme selon le type demandé
-    pub(crate) fn new(generator_type: AlgorithmKind) -> Self {
+    pub fn new(generator_type: AlgorithmKind) -> Self {
         match generator_type {
             AlgorithmKind::Backtracking => Self::Backtracking(BacktrackingGenerator::default()),
             AlgorithmKind::Prim => Self::Prim(PrimGenerator::default()),
@@ -72,6 +205,69 @@ impl Algorithm {
             AlgorithmKind::RecursiveDivision => Self::RecursiveDivision(RecursiveDivisionGenerator::default()),
         }
     }
+
+    /// Créer un algorithme reproductible : une même graine donne toujours le même labyrinthe
+    pub fn with_seed(generator_type: AlgorithmKind, seed: u64) -> Self {
+        match generator_type {
+            AlgorithmKind::Backtracking => Self::Backtracking(BacktrackingGenerator::with_seed(seed)),
+            AlgorithmKind::Prim => Self::Prim(PrimGenerator::with_seed(seed)),
+            AlgorithmKind::Kruskal => Self::Kruskal(KruskalGenerator::with_seed(seed)),
+            AlgorithmKind::Wilson => Self::Wilson(WilsonGenerator::with_seed(seed)),
+            AlgorithmKind::RecursiveDivision => Self::RecursiveDivision(RecursiveDivisionGenerator::with_seed(seed)),
+        }
+    }
+
+    /// Type de l'algorithme ; None pour un algorithme externe
+    pub fn kind(&self) -> Option<AlgorithmKind> {
+        match self {
+            Self::Backtracking(_) => Some(AlgorithmKind::Backtracking),
+            Self::Prim(_) => Some(AlgorithmKind::Prim),
+            Self::Kruskal(_) => Some(AlgorithmKind::Kruskal),
+            Self::Wilson(_) => Some(AlgorithmKind::Wilson),
+            Self::RecursiveDivision(_) => Some(AlgorithmKind::RecursiveDivision),
+            Self::Custom(_) => None,
+        }
+    }
+
+    /// État complet de l'algorithme, précédé de son type, pour un instantané
+    /// Un algorithme externe ne peut pas être sauvegardé
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) -> Result<(), MazeError> {
+        let kind = self.kind().ok_or(MazeError::SnapshotUnsupported(self.get_name()))?;
+        bytes.push(kind as u8);
+        match self {
+            Self::Backtracking(generator) => generator.save(bytes),
+            Self::Prim(generator) => generator.save(bytes),
+            Self::Kruskal(generator) => generator.save(bytes),
+            Self::Wilson(generator) => generator.save(bytes),
+            Self::RecursiveDivision(generator) => generator.save(bytes),
+            // Refusé plus haut
+            Self::Custom(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Relit `save` pour la grille restaurée
+    pub(crate) fn load(reader: &mut Reader, grid: &MazeGrid) -> Result<Self, DecodeError> {
+        let cell_count = grid.cell_count();
+        let kind = AlgorithmKind::ALL.get(reader.byte()? as usize).ok_or(DecodeError::InvalidState)?;
+        Ok(match kind {
+            AlgorithmKind::Backtracking => Self::Backtracking(BacktrackingGenerator::load(reader, cell_count)?),
+            AlgorithmKind::Prim => Self::Prim(PrimGenerator::load(reader, cell_count)?),
+            AlgorithmKind::Kruskal => Self::Kruskal(KruskalGenerator::load(reader, grid)?),
+            AlgorithmKind::Wilson => Self::Wilson(WilsonGenerator::load(reader, cell_count)?),
+            AlgorithmKind::RecursiveDivision => Self::RecursiveDivision(RecursiveDivisionGenerator::load(reader, grid)?),
+        })
+    }
+
+    /// Activer le tissage (passages dessus/dessous) ; seuls Backtracking et Kruskal le permettent
+    /// Retourne None pour les autres algorithmes
+    pub fn weave(self, enabled: bool) -> Option<Self> {
+        match self {
+            Self::Backtracking(generator) => Some(Self::Backtracking(generator.weave(enabled))),
+            Self::Kruskal(generator) => Some(Self::Kruskal(generator.weave(enabled))),
+            _ => None,
+        }
+    }
 }
 
 impl GenerationAlgorithm for Algorithm {
@@ -83,6 +279,7 @@ impl GenerationAlgorithm for Algorithm {
             Self::Kruskal(generator) => generator.start(grid),
             Self::Wilson(generator) => generator.start(grid),
             Self::RecursiveDivision(generator) => generator.start(grid),
+            Self::Custom(generator) => generator.start(grid),
         }
     }
 
@@ -94,46 +291,85 @@ impl GenerationAlgorithm for Algorithm {
             Self::Kruskal(generator) => generator.step(grid),
             Self::Wilson(generator) => generator.step(grid),
             Self::RecursiveDivision(generator) => generator.step(grid),
+            Self::Custom(generator) => generator.step(grid),
         }
     }
     
     /// Vérifier si la génération est terminée
+    // Les générateurs génériques implémentent le trait pour toute topologie : on précise MazeGrid
     fn is_finished(&self) -> bool {
         match self {
-            Self::Backtracking(generator) => generator.is_finished(),
-            Self::Prim(generator) => generator.is_finished(),
-            Self::Kruskal(generator) => generator.is_finished(),
-            Self::Wilson(generator) => generator.is_finished(),
+            Self::Backtracking(generator) => GenerationAlgorithm::<MazeGrid>::is_finished(generator),
+            Self::Prim(generator) => GenerationAlgorithm::<MazeGrid>::is_finished(generator),
+            Self::Kruskal(generator) => GenerationAlgorithm::<MazeGrid>::is_finished(generator),
+            Self::Wilson(generator) => GenerationAlgorithm::<MazeGrid>::is_finished(generator),
             Self::RecursiveDivision(generator) => generator.is_finished(),
+            Self::Custom(generator) => generator.is_finished(),
         }
     }
     
     /// Obtenir le nom de l'algorithme
     fn get_name(&self) -> &'static str {
         match self {
-            Self::Backtracking(generator) => generator.get_name(),
-            Self::Prim(generator) => generator.get_name(),
-            Self::Kruskal(generator) => generator.get_name(),
-            Self::Wilson(generator) => generator.get_name(),
+            Self::Backtracking(generator) => GenerationAlgorithm::<MazeGrid>::get_name(generator),
+            Self::Prim(generator) => GenerationAlgorithm::<MazeGrid>::get_name(generator),
+            Self::Kruskal(generator) => GenerationAlgorithm::<MazeGrid>::get_name(generator),
+            Self::Wilson(generator) => GenerationAlgorithm::<MazeGrid>::get_name(generator),
             Self::RecursiveDivision(generator) => generator.get_name(),
+            Self::Custom(generator) => generator.get_name(),
         }
     }
 
     /// Obtenir les layers de cellules à afficher
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    fn get_cell_layers(&self, grid: &MazeGrid) -> Vec<Vec<Point>> {
+        match self {
+            Self::Backtracking(generator) => generator.get_cell_layers(grid),
+            Self::Prim(generator) => generator.get_cell_layers(grid),
+            Self::Kruskal(generator) => generator.get_cell_layers(grid),
+            Self::Wilson(generator) => generator.get_cell_layers(grid),
+            Self::RecursiveDivision(generator) => generator.get_cell_layers(grid),
+            Self::Custom(generator) => generator.get_cell_layers(grid),
+        }
+    }
+
+    /// Obtenir les changements des layers depuis l'appel précédent, si l'algorithme les suit
+    fn take_layer_deltas(&mut self, grid: &MazeGrid) -> Option<Vec<LayerDelta>> {
         match self {
-            Self::Backtracking(generator) => generator.get_cell_layers(),
-            Self::Prim(generator) => generator.get_cell_layers(),
-            Self::Kruskal(generator) => generator.get_cell_layers(),
-            Self::Wilson(generator) => generator.get_cell_layers(),
-            Self::RecursiveDivision(generator) => generator.get_cell_layers(),
+            Self::Backtracking(generator) => generator.take_layer_deltas(grid),
+            Self::Prim(generator) => generator.take_layer_deltas(grid),
+            Self::Kruskal(generator) => generator.take_layer_deltas(grid),
+            Self::Wilson(generator) => generator.take_layer_deltas(grid),
+            Self::RecursiveDivision(generator) => generator.take_layer_deltas(grid),
+            Self::Custom(generator) => generator.take_layer_deltas(grid),
+        }
+    }
+
+    /// Obtenir l'avancement de la génération
+    fn progress(&self) -> f64 {
+        match self {
+            Self::Backtracking(generator) => GenerationAlgorithm::<MazeGrid>::progress(generator),
+            Self::Prim(generator) => GenerationAlgorithm::<MazeGrid>::progress(generator),
+            Self::Kruskal(generator) => GenerationAlgorithm::<MazeGrid>::progress(generator),
+            Self::Wilson(generator) => GenerationAlgorithm::<MazeGrid>::progress(generator),
+            Self::RecursiveDivision(generator) => generator.progress(),
+            Self::Custom(generator) => generator.progress(),
         }
     }
 }
 
-pub trait GenerationAlgorithm {
-    fn start(&mut self, grid: &mut MazeGrid);
-    fn step(&mut self, grid: &mut MazeGrid) -> (GenerationResult, Vec<WallChange>);
+/// Interface commune des algorithmes de génération
+/// Implémentable hors de la crate puis utilisable via `Algorithm::Custom`
+///
+/// Le paramètre `G` est la topologie de la grille (carrée par défaut).
+/// Backtracking, Prim, Kruskal et Wilson fonctionnent sur toute `Topology`
+pub trait GenerationAlgorithm<G: Topology = MazeGrid> {
+    /// Initialiser l'état interne et préparer la grille (la remplir de murs, la vider...)
+    fn start(&mut self, grid: &mut G);
+
+    /// Avancer d'une étape et retourner les murs modifiés pendant cette étape
+    /// Les modifications doivent passer par l'API publique de la grille (`link`, `set_wall`...)
+    fn step(&mut self, grid: &mut G) -> (GenerationResult, Vec<G::Change>);
+
     fn is_finished(&self) -> bool;
     fn get_name(&self) -> &'static str;
 
@@ -141,7 +377,25 @@ pub trait GenerationAlgorithm {
     /// - Layer 0 : Cellules principales (ex: chemin actuel)
     /// - Layer 1 : Cellules secondaires (ex: dans le labyrinthe)
     /// - Layer 2+ : Autres cellules (ex: frontière, cellules restantes)
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    fn get_cell_layers(&self, _grid: &G) -> Vec<Vec<Point>> {
         Vec::new()  // Implémentation par défaut : aucune cellule
     }
-}
\ No newline at end of file
+
+    /// Cellules entrées dans un layer ou sorties depuis l'appel précédent, dans l'ordre
+    /// Retourne None si l'algorithme ne suit pas lui-même ses layers : l'appelant compare
+    /// alors `get_cell_layers` au résultat précédent (voir `MazeGenerator::take_layer_deltas`)
+    /// Un algorithme qui suit ses layers peut retourner None au premier appel, pour activer le suivi
+    fn take_layer_deltas(&mut self, _grid: &G) -> Option<Vec<LayerDelta>> {
+        None
+    }
+
+    /// Avancement de la génération, entre 0 et 1, pour une barre de progression
+    /// Implémentation par défaut : 0 jusqu'à la fin, puis 1
+    fn progress(&self) -> f64 {
+        if self.is_finished() { 1.0 } else { 0.0 }
+    }
+}
+// Fraction done / total, nulle avant le démarrage (total = 0)
+fn ratio(done: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { done as f64 / total as f64 }
+}
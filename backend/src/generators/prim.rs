@@ -1,173 +1,143 @@
-use super::{GenerationAlgorithm, GenerationResult, WallChange, WallType};
-use crate::maze_grid::MazeGrid;
+use super::{GenerationAlgorithm, GenerationResult, MazeRng, ratio};
 use crate::Point;
+use crate::codec::{Reader, write_bools, write_option, write_varint};
+use crate::export::DecodeError;
+use crate::topology::Topology;
 use rand::Rng;
 
 // Structure pour représenter un mur de frontière
+// (entre une cellule du labyrinthe et un voisin qui n'y était pas encore)
 struct Wall {
-    x: usize,
-    y: usize,
-    wall_type: WallType,
-    neighbor_x: usize,
-    neighbor_y: usize,
+    cell: usize,
+    neighbor: usize,
 }
 
 #[derive(Default)]
 pub struct PrimGenerator {
+    // Générateur aléatoire (graine fixée pour une génération reproductible)
+    rng: MazeRng,
+
     // État des cellules : true = dans le labyrinthe, false = hors du labyrinthe
     in_maze: Vec<bool>,
 
+    // Nombre de cellules dans le labyrinthe
+    carved: usize,
+
     // Liste des murs de frontière (murs entre cellules "in" et "out")
     frontier_walls: Vec<Wall>,
 
     // Position courante pour l'animation (dernière cellule ajoutée)
-    current_point: Option<Point>,
+    current_cell: Option<usize>,
 
     // État de fin
     is_finished: bool,
-
-    // Dimensions
-    width: usize,
-    height: usize,
 }
 
 impl PrimGenerator {
+    /// Créer un générateur reproductible à partir d'une graine
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: MazeRng::from_seed(seed), ..Self::default() }
+    }
 
-    fn add_walls_to_frontier(&mut self, grid: &MazeGrid, point: Point) {
-        // Vérifier droite (mur vertical)
-        if point.x < self.width - 1 {
-            let neighbor_x = point.x + 1;
-            let neighbor_index = grid.get_index(neighbor_x, point.y);
-            if !self.in_maze[neighbor_index] {
-                self.frontier_walls.push(Wall {
-                    x: point.x,
-                    y: point.y,
-                    wall_type: WallType::Vertical,
-                    neighbor_x,
-                    neighbor_y: point.y,
-                });
+    fn add_walls_to_frontier<G: Topology>(&mut self, grid: &G, cell: usize) {
+        // Ajouter les murs vers tous les voisins encore hors du labyrinthe
+        for neighbor in grid.neighbors(cell) {
+            if !self.in_maze[neighbor] {
+                self.frontier_walls.push(Wall { cell, neighbor });
             }
         }
+    }
 
-        // Vérifier bas (mur horizontal)
-        if point.y < self.height - 1 {
-            let neighbor_y = point.y + 1;
-            let neighbor_index = grid.get_index(point.x, neighbor_y);
-            if !self.in_maze[neighbor_index] {
-                self.frontier_walls.push(Wall {
-                    x: point.x,
-                    y: point.y,
-                    wall_type: WallType::Horizontal,
-                    neighbor_x: point.x,
-                    neighbor_y,
-                });
-            }
+    /// État complet, pour un instantané (`MazeGenerator::snapshot`)
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) {
+        self.rng.save(bytes);
+        write_bools(bytes, &self.in_maze);
+        write_varint(bytes, self.carved);
+        write_varint(bytes, self.frontier_walls.len());
+        for wall in &self.frontier_walls {
+            write_varint(bytes, wall.cell);
+            write_varint(bytes, wall.neighbor);
         }
+        write_option(bytes, self.current_cell);
+        bytes.push(u8::from(self.is_finished));
+    }
 
-        // Vérifier gauche (mur vertical défini par le voisin)
-        if point.x > 0 {
-            let neighbor_x = point.x - 1;
-            let neighbor_index = grid.get_index(neighbor_x, point.y);
-            if !self.in_maze[neighbor_index] {
-                self.frontier_walls.push(Wall {
-                    x: neighbor_x,
-                    y: point.y,
-                    wall_type: WallType::Vertical,
-                    neighbor_x,
-                    neighbor_y: point.y,
-                });
-            }
+    /// Relit `save` pour une grille de `cell_count` cellules
+    pub(crate) fn load(reader: &mut Reader, cell_count: usize) -> Result<Self, DecodeError> {
+        let rng = MazeRng::load(reader)?;
+        let in_maze = reader.bools(cell_count)?;
+        let carved = reader.varint()?;
+        if carved > cell_count {
+            return Err(DecodeError::InvalidState);
         }
+        let frontier_walls = (0..reader.list_len()?)
+            .map(|_| Ok(Wall { cell: reader.cell(cell_count)?, neighbor: reader.cell(cell_count)? }))
+            .collect::<Result<_, DecodeError>>()?;
 
-        // Vérifier haut (mur horizontal défini par le voisin)
-        if point.y > 0 {
-            let neighbor_y = point.y - 1;
-            let neighbor_index = grid.get_index(point.x, neighbor_y);
-            if !self.in_maze[neighbor_index] {
-                self.frontier_walls.push(Wall {
-                    x: point.x,
-                    y: neighbor_y,
-                    wall_type: WallType::Horizontal,
-                    neighbor_x: point.x,
-                    neighbor_y,
-                });
-            }
-        }
+        Ok(Self { rng, in_maze, carved, frontier_walls, current_cell: reader.option(cell_count)?, is_finished: reader.bool()? })
     }
 }
 
-impl GenerationAlgorithm for PrimGenerator {
-    fn start(&mut self, grid: &mut MazeGrid) {
-        self.width = grid.width;
-        self.height = grid.height;
-        self.in_maze = vec![false; self.width * self.height];
+impl<G: Topology> GenerationAlgorithm<G> for PrimGenerator {
+    fn start(&mut self, grid: &mut G) {
+        self.in_maze = vec![false; grid.cell_count()];
         self.frontier_walls = Vec::new();
         self.is_finished = false;
 
         // Remplir la grille de murs
-        grid.fill_grid();
+        grid.fill_walls();
+
+        // Topologie vide : rien à creuser
+        if grid.cell_count() == 0 {
+            self.carved = 0;
+            self.current_cell = None;
+            self.is_finished = true;
+            return;
+        }
 
         // Choisir une cellule de départ aléatoire
-        let mut rng = rand::rng();
-        let start_x = rng.random_range(0..self.width);
-        let start_y = rng.random_range(0..self.height);
-        let start_point = Point { x: start_x, y: start_y };
+        let start_cell = self.rng.random_range(0..grid.cell_count());
 
         // Marquer cette cellule comme dans le labyrinthe
-        let start_index = grid.get_index(start_x, start_y);
-        self.in_maze[start_index] = true;
-        self.current_point = Some(start_point);
+        self.in_maze[start_cell] = true;
+        self.carved = 1;
+        self.current_cell = Some(start_cell);
 
         // Ajouter tous les murs de cette cellule à la frontière
-        self.add_walls_to_frontier(grid, start_point);
+        self.add_walls_to_frontier(grid, start_cell);
     }
 
-    fn step(&mut self, grid: &mut MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+    fn step(&mut self, grid: &mut G) -> (GenerationResult, Vec<G::Change>) {
         // Si la frontière est vide, terminé
         if self.frontier_walls.is_empty() {
             self.is_finished = true;
-            self.current_point = None;
+            self.current_cell = None;
             return (GenerationResult::Finished, Vec::new());
         }
 
         // Choisir un mur aléatoire de la frontière
-        let mut rng = rand::rng();
-        let wall_index = rng.random_range(0..self.frontier_walls.len());
+        let wall_index = self.rng.random_range(0..self.frontier_walls.len());
         let wall = self.frontier_walls.swap_remove(wall_index);
 
         // Vérifier si le voisin est toujours hors du labyrinthe
-        let neighbor_index = grid.get_index(wall.neighbor_x, wall.neighbor_y);
-
-        if !self.in_maze[neighbor_index] {
+        if !self.in_maze[wall.neighbor] {
             // Le voisin est hors du labyrinthe, on peut le connecter
 
             // Supprimer le mur
-            match wall.wall_type {
-                WallType::Vertical => grid.remove_vertical_wall(wall.x, wall.y),
-                WallType::Horizontal => grid.remove_horizontal_wall(wall.x, wall.y),
-            }
+            let wall_changes = grid.link(wall.cell, wall.neighbor).into_iter().collect();
 
             // Marquer le voisin comme dans le labyrinthe
-            self.in_maze[neighbor_index] = true;
+            self.in_maze[wall.neighbor] = true;
+            self.carved += 1;
 
             // Mettre à jour la position courante
-            self.current_point = Some(Point {
-                x: wall.neighbor_x,
-                y: wall.neighbor_y
-            });
+            self.current_cell = Some(wall.neighbor);
 
             // Ajouter les murs du voisin à la frontière
-            self.add_walls_to_frontier(grid, Point {
-                x: wall.neighbor_x,
-                y: wall.neighbor_y,
-            });
+            self.add_walls_to_frontier(grid, wall.neighbor);
 
             // Retourner le changement de mur
-            return (GenerationResult::Continue, vec![WallChange {
-                x: wall.x,
-                y: wall.y,
-                wall_type: wall.wall_type,
-            }]);
+            return (GenerationResult::Continue, wall_changes);
         }
 
         // Le voisin est déjà dans le labyrinthe, continuer sans changement
@@ -182,12 +152,56 @@ impl GenerationAlgorithm for PrimGenerator {
         "Randomized Prim"
     }
 
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    // Cellules dans le labyrinthe ; les murs restant ensuite dans la frontière sont écartés sans rien creuser
+    fn progress(&self) -> f64 {
+        if self.is_finished { 1.0 } else { ratio(self.carved, self.in_maze.len()) }
+    }
+
+    fn get_cell_layers(&self, grid: &G) -> Vec<Vec<Point>> {
         // Layer 0 : La cellule courante
-        if let Some(point) = self.current_point {
-            vec![vec![point]]
+        if let Some(cell) = self.current_cell {
+            vec![vec![grid.cell_point(cell)]]
         } else {
             vec![]
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_grid::MazeGrid;
+
+    fn reload(generator: &PrimGenerator, cell_count: usize) -> Result<PrimGenerator, DecodeError> {
+        let mut bytes = Vec::new();
+        generator.save(&mut bytes);
+        PrimGenerator::load(&mut Reader::new(&bytes), cell_count)
+    }
+
+    #[test]
+    fn load_round_trips_state() {
+        let mut grid = MazeGrid::new(4, 3);
+        let mut generator = PrimGenerator::with_seed(5);
+        GenerationAlgorithm::<MazeGrid>::start(&mut generator, &mut grid);
+        for _ in 0..5 {
+            generator.step(&mut grid);
+        }
+
+        let loaded = reload(&generator, 12).unwrap();
+        assert_eq!(loaded.in_maze, generator.in_maze);
+        assert_eq!(loaded.carved, generator.carved);
+        assert_eq!(loaded.frontier_walls.len(), generator.frontier_walls.len());
+    }
+
+    #[test]
+    fn load_rejects_more_carved_cells_than_the_grid() {
+        let mut grid = MazeGrid::new(4, 3);
+        let mut generator = PrimGenerator::with_seed(5);
+        GenerationAlgorithm::<MazeGrid>::start(&mut generator, &mut grid);
+
+        generator.carved = 13;
+        assert_eq!(reload(&generator, 12).err(), Some(DecodeError::InvalidState));
+        generator.carved = 12;
+        assert!(reload(&generator, 12).is_ok());
+    }
+}
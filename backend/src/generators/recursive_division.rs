@@ -1,4 +1,6 @@
-use super::{GenerationAlgorithm, GenerationResult, WallChange, WallType};
+use super::{GenerationAlgorithm, GenerationResult, MazeRng, WallChange, WallOp, WallType, ratio};
+use crate::codec::{Reader, write_option, write_varint};
+use crate::export::DecodeError;
 use crate::maze_grid::MazeGrid;
 use crate::Point;
 use rand::Rng;
@@ -31,6 +33,9 @@ impl Chamber {
 
 #[derive(Default)]
 pub struct RecursiveDivisionGenerator {
+    // Générateur aléatoire (graine fixée pour une génération reproductible)
+    rng: MazeRng,
+
     // Pile des chambres à diviser
     chambers: Vec<Chamber>,
 
@@ -46,18 +51,75 @@ pub struct RecursiveDivisionGenerator {
 }
 
 impl RecursiveDivisionGenerator {
+    /// Créer un générateur reproductible à partir d'une graine
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: MazeRng::from_seed(seed), ..Self::default() }
+    }
+
+    /// État complet, pour un instantané (`MazeGenerator::snapshot`)
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) {
+        self.rng.save(bytes);
+        write_varint(bytes, self.chambers.len());
+        for chamber in &self.chambers {
+            for bound in [chamber.x_min, chamber.y_min, chamber.x_max, chamber.y_max] {
+                write_varint(bytes, bound);
+            }
+        }
+        write_option(bytes, self.current_point.map(|point| point.y * self.width + point.x));
+        bytes.push(u8::from(self.is_finished));
+    }
+
+    /// Relit `save` ; les chambres doivent être non vides, tenir dans la grille et ne pas se chevaucher
+    pub(crate) fn load(reader: &mut Reader, grid: &MazeGrid) -> Result<Self, DecodeError> {
+        let (width, height) = (grid.width, grid.height);
+        let rng = MazeRng::load(reader)?;
+        let chambers: Vec<Chamber> = (0..reader.list_len()?)
+            .map(|_| {
+                let chamber = Chamber {
+                    x_min: reader.varint()?,
+                    y_min: reader.varint()?,
+                    x_max: reader.varint()?,
+                    y_max: reader.varint()?,
+                };
+                let valid = chamber.x_min < chamber.x_max
+                    && chamber.x_max <= width
+                    && chamber.y_min < chamber.y_max
+                    && chamber.y_max <= height;
+                if valid { Ok(chamber) } else { Err(DecodeError::InvalidState) }
+            })
+            .collect::<Result<_, DecodeError>>()?;
+
+        // Surface totale bornée par la grille, puis marquage des cellules (coût borné par la grille)
+        let area = chambers.iter().try_fold(0usize, |area, chamber| area.checked_add(chamber.width() * chamber.height()));
+        if area.is_none_or(|area| area > width * height) {
+            return Err(DecodeError::InvalidState);
+        }
+        let mut covered = vec![false; width * height];
+        for chamber in &chambers {
+            for y in chamber.y_min..chamber.y_max {
+                for cell in &mut covered[y * width + chamber.x_min..y * width + chamber.x_max] {
+                    if std::mem::replace(cell, true) {
+                        return Err(DecodeError::InvalidState);
+                    }
+                }
+            }
+        }
+
+        let current_point = reader.option(width * height)?.map(|cell| Point { x: cell % width, y: cell / width });
+
+        Ok(Self { rng, chambers, current_point, is_finished: reader.bool()?, width, height })
+    }
 
     fn divide_chamber_horizontally(
         &mut self,
         grid: &mut MazeGrid,
         chamber: Chamber,
-        rng: &mut impl rand::RngCore,
     ) -> (GenerationResult, Vec<WallChange>) {
         // Choisir une ligne pour le mur horizontal
-        let wall_y = chamber.y_min + rng.random_range(0..(chamber.height() - 1));
+        let wall_y = chamber.y_min + self.rng.random_range(0..(chamber.height() - 1));
 
         // Choisir une position pour le passage
-        let passage_x = chamber.x_min + rng.random_range(0..chamber.width());
+        let passage_x = chamber.x_min + self.rng.random_range(0..chamber.width());
 
         // Ajouter le mur horizontal, sauf au passage
         let mut wall_changes = Vec::new();
@@ -69,6 +131,7 @@ impl RecursiveDivisionGenerator {
                     x,
                     y: wall_y,
                     wall_type: WallType::Horizontal,
+                    op: WallOp::Added,
                 });
             }
         }
@@ -98,13 +161,12 @@ impl RecursiveDivisionGenerator {
         &mut self,
         grid: &mut MazeGrid,
         chamber: Chamber,
-        rng: &mut impl rand::RngCore,
     ) -> (GenerationResult, Vec<WallChange>) {
         // Choisir une colonne pour le mur vertical
-        let wall_x = chamber.x_min + rng.random_range(0..(chamber.width() - 1));
+        let wall_x = chamber.x_min + self.rng.random_range(0..(chamber.width() - 1));
 
         // Choisir une position pour le passage
-        let passage_y = chamber.y_min + rng.random_range(0..chamber.height());
+        let passage_y = chamber.y_min + self.rng.random_range(0..chamber.height());
 
         // Ajouter le mur vertical, sauf au passage
         let mut wall_changes = Vec::new();
@@ -116,6 +178,7 @@ impl RecursiveDivisionGenerator {
                     x: wall_x,
                     y,
                     wall_type: WallType::Vertical,
+                    op: WallOp::Added,
                 });
             }
         }
@@ -142,7 +205,8 @@ impl RecursiveDivisionGenerator {
     }
 }
 
-impl GenerationAlgorithm for RecursiveDivisionGenerator {
+// Algorithme propre à la grille carrée : il découpe des chambres rectangulaires
+impl GenerationAlgorithm<MazeGrid> for RecursiveDivisionGenerator {
     fn start(&mut self, grid: &mut MazeGrid) {
         self.width = grid.width;
         self.height = grid.height;
@@ -150,6 +214,23 @@ impl GenerationAlgorithm for RecursiveDivisionGenerator {
         // IMPORTANT : Commencer avec une grille VIDE (pas de murs)
         grid.clear_grid();
 
+        // Les chambres sont rectangulaires : en mode torique, fermer les raccords
+        // pour que le découpage produise un labyrinthe parfait
+        for y in 0..self.height {
+            grid.set_wall(self.width.saturating_sub(1), y, WallType::Vertical, true);
+        }
+        for x in 0..self.width {
+            grid.set_wall(x, self.height.saturating_sub(1), WallType::Horizontal, true);
+        }
+
+        // Grille vide : rien à diviser
+        if self.width == 0 || self.height == 0 {
+            self.chambers = Vec::new();
+            self.is_finished = true;
+            self.current_point = None;
+            return;
+        }
+
         // Ajouter la chambre initiale (toute la grille)
         self.chambers = vec![Chamber {
             x_min: 0,
@@ -183,24 +264,23 @@ impl GenerationAlgorithm for RecursiveDivisionGenerator {
         }
 
         // Choisir l'orientation
-        let mut rng = rand::rng();
         let divide_horizontally = if can_horizontal && can_vertical {
             // Les deux sont possibles, choisir avec biais selon les proportions
             if chamber.width() > chamber.height() {
-                rng.random_range(0..4) == 0  // 25% de chance de horizontal si plus large
+                self.rng.random_range(0..4) == 0  // 25% de chance de horizontal si plus large
             } else if chamber.height() > chamber.width() {
-                rng.random_range(0..4) != 0  // 75% de chance de horizontal si plus haut
+                self.rng.random_range(0..4) != 0  // 75% de chance de horizontal si plus haut
             } else {
-                rng.random_range(0..2) == 0  // 50/50 si carré
+                self.rng.random_range(0..2) == 0  // 50/50 si carré
             }
         } else {
             can_horizontal
         };
 
         if divide_horizontally {
-            self.divide_chamber_horizontally(grid, chamber, &mut rng)
+            self.divide_chamber_horizontally(grid, chamber)
         } else {
-            self.divide_chamber_vertically(grid, chamber, &mut rng)
+            self.divide_chamber_vertically(grid, chamber)
         }
     }
 
@@ -212,7 +292,17 @@ impl GenerationAlgorithm for RecursiveDivisionGenerator {
         "Recursive Division"
     }
 
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    // Surface des chambres restant à diviser, sur celle de la grille
+    fn progress(&self) -> f64 {
+        if self.is_finished {
+            return 1.0;
+        }
+        let total = self.width * self.height;
+        let pending: usize = self.chambers.iter().map(|chamber| chamber.width() * chamber.height()).sum();
+        ratio(total.saturating_sub(pending), total)
+    }
+
+    fn get_cell_layers(&self, _grid: &MazeGrid) -> Vec<Vec<Point>> {
         // Layer 0 : La cellule courante (le passage créé)
         if let Some(point) = self.current_point {
             vec![vec![point]]
@@ -221,3 +311,58 @@ impl GenerationAlgorithm for RecursiveDivisionGenerator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reload(generator: &RecursiveDivisionGenerator, grid: &MazeGrid) -> Result<RecursiveDivisionGenerator, DecodeError> {
+        let mut bytes = Vec::new();
+        generator.save(&mut bytes);
+        RecursiveDivisionGenerator::load(&mut Reader::new(&bytes), grid)
+    }
+
+    #[test]
+    fn load_round_trips_chambers() {
+        let mut grid = MazeGrid::new(6, 5);
+        let mut generator = RecursiveDivisionGenerator::with_seed(3);
+        generator.start(&mut grid);
+        for _ in 0..4 {
+            generator.step(&mut grid);
+        }
+
+        let loaded = reload(&generator, &grid).unwrap();
+        let bounds = |generator: &RecursiveDivisionGenerator| -> Vec<_> {
+            generator.chambers.iter().map(|c| (c.x_min, c.y_min, c.x_max, c.y_max)).collect()
+        };
+        assert_eq!(bounds(&loaded), bounds(&generator));
+        assert_eq!(loaded.progress(), generator.progress());
+    }
+
+    #[test]
+    fn load_rejects_overlapping_chambers() {
+        let mut grid = MazeGrid::new(4, 4);
+        let mut generator = RecursiveDivisionGenerator::with_seed(1);
+        generator.start(&mut grid);
+
+        // Deux chambres couvrant toute la grille
+        generator.chambers.push(Chamber { x_min: 0, y_min: 0, x_max: 4, y_max: 4 });
+        assert_eq!(reload(&generator, &grid).err(), Some(DecodeError::InvalidState));
+
+        // Surface totale correcte, mais deux chambres qui se recouvrent
+        generator.chambers = vec![
+            Chamber { x_min: 0, y_min: 0, x_max: 3, y_max: 4 },
+            Chamber { x_min: 2, y_min: 0, x_max: 3, y_max: 4 },
+        ];
+        assert_eq!(reload(&generator, &grid).err(), Some(DecodeError::InvalidState));
+    }
+
+    #[test]
+    fn progress_saturates_on_excess_area() {
+        let mut grid = MazeGrid::new(4, 4);
+        let mut generator = RecursiveDivisionGenerator::with_seed(1);
+        generator.start(&mut grid);
+        generator.chambers.push(Chamber { x_min: 0, y_min: 0, x_max: 4, y_max: 4 });
+        assert_eq!(generator.progress(), 0.0);
+    }
+}
@@ -1,8 +1,13 @@
-use super::{GenerationAlgorithm, GenerationResult, WallChange, WallType};
-use crate::maze_grid::MazeGrid;
+use super::{GenerationAlgorithm, GenerationResult, LayerDelta, MazeRng, ratio};
 use crate::Point;
+use crate::codec::{Reader, write_cells};
+use crate::export::DecodeError;
+use crate::topology::Topology;
 use rand::Rng;
 
+// Marqueur des tables d'index : cellule absente de la liste
+const ABSENT: usize = usize::MAX;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum WilsonState {
     // Initialisation : choisir une nouvelle cellule de départ
@@ -16,77 +21,161 @@ enum WilsonState {
 }
 
 pub struct WilsonGenerator {
+    // Générateur aléatoire (graine fixée pour une génération reproductible)
+    rng: MazeRng,
+
     // État des cellules : true = dans le labyrinthe, false = hors du labyrinthe
     in_maze: Vec<bool>,
 
-    // Chemin de la marche aléatoire courante (liste simple de points)
-    current_path: Vec<Point>,
+    // Chemin de la marche aléatoire courante (liste simple de cellules)
+    current_path: Vec<usize>,
+
+    // Position de chaque cellule dans current_path, ou ABSENT : détection des boucles en O(1)
+    path_index: Vec<usize>,
 
-    // Liste des cellules pas encore dans le labyrinthe (pour optimisation)
-    remaining_cells: Vec<Point>,
+    // Liste des cellules pas encore dans le labyrinthe (tirage de la cellule de départ)
+    remaining_cells: Vec<usize>,
+
+    // Position de chaque cellule dans remaining_cells, ou ABSENT : retrait en O(1)
+    remaining_index: Vec<usize>,
 
     // État de la génération
     state: WilsonState,
 
-    // Dimensions
-    width: usize,
-    height: usize,
+    // Changements des layers depuis le dernier appel à take_layer_deltas : (layer, cellule, entrée)
+    // Actif seulement après un premier appel, pour ne rien accumuler si personne ne les lit
+    layer_events: Option<Vec<(usize, usize, bool)>>,
 }
 
 impl Default for WilsonGenerator {
     fn default() -> Self {
         Self {
+            rng: MazeRng::default(),
             in_maze: Vec::new(),
             current_path: Vec::new(),
+            path_index: Vec::new(),
             remaining_cells: Vec::new(),
+            remaining_index: Vec::new(),
             state: WilsonState::PickingStart,
-            width: 0,
-            height: 0,
+            layer_events: None,
         }
     }
 }
 
 impl WilsonGenerator {
+    /// Créer un générateur reproductible à partir d'une graine
+    pub fn with_seed(seed: u64) -> Self {
+        Self { rng: MazeRng::from_seed(seed), ..Self::default() }
+    }
 
-    fn get_valid_neighbors(&self, _grid: &MazeGrid, point: Point) -> Vec<Point> {
-        let mut neighbors = Vec::new();
-
-        // Est
-        if point.x + 1 < self.width {
-            neighbors.push(Point { x: point.x + 1, y: point.y });
+    // Layers : 0 = chemin de la marche, 1 = cellules dans le labyrinthe
+    fn record(&mut self, layer: usize, cell: usize, entered: bool) {
+        if let Some(events) = &mut self.layer_events {
+            events.push((layer, cell, entered));
         }
-        // Ouest
-        if point.x > 0 {
-            neighbors.push(Point { x: point.x - 1, y: point.y });
+    }
+
+    fn push_path(&mut self, cell: usize) {
+        self.path_index[cell] = self.current_path.len();
+        self.current_path.push(cell);
+        self.record(0, cell, true);
+    }
+
+    // Raccourcit le chemin à `len` cellules ; chaque cellule retirée y était entrée une fois,
+    // le coût total reste donc proportionnel au nombre de pas de la marche
+    fn truncate_path(&mut self, len: usize) {
+        for &cell in &self.current_path[len.min(self.current_path.len())..] {
+            self.path_index[cell] = ABSENT;
+            if let Some(events) = &mut self.layer_events {
+                events.push((0, cell, false));
+            }
         }
-        // Sud
-        if point.y + 1 < self.height {
-            neighbors.push(Point { x: point.x, y: point.y + 1 });
+        self.current_path.truncate(len);
+    }
+
+    // Retire une cellule de remaining_cells ; l'ordre obtenu est celui d'un swap_remove
+    fn remove_remaining(&mut self, cell: usize) {
+        let position = std::mem::replace(&mut self.remaining_index[cell], ABSENT);
+        if position == ABSENT {
+            return;
         }
-        // Nord
-        if point.y > 0 {
-            neighbors.push(Point { x: point.x, y: point.y - 1 });
+
+        self.remaining_cells.swap_remove(position);
+        if let Some(&moved) = self.remaining_cells.get(position) {
+            self.remaining_index[moved] = position;
         }
+    }
 
-        neighbors
+    /// État complet, pour un instantané (`MazeGenerator::snapshot`)
+    /// Les tables d'index et les cellules du labyrinthe se déduisent du chemin et des cellules restantes
+    pub(crate) fn save(&self, bytes: &mut Vec<u8>) {
+        self.rng.save(bytes);
+        write_cells(bytes, &self.current_path);
+        // L'ordre des cellules restantes compte : le tirage de la prochaine marche en dépend
+        write_cells(bytes, &self.remaining_cells);
+        bytes.push(match self.state {
+            WilsonState::PickingStart => 0,
+            WilsonState::Walking => 1,
+            WilsonState::CarvingPath => 2,
+            WilsonState::Finished => 3,
+        });
     }
 
+    /// Relit `save` pour une grille de `cell_count` cellules
+    pub(crate) fn load(reader: &mut Reader, cell_count: usize) -> Result<Self, DecodeError> {
+        let rng = MazeRng::load(reader)?;
+        let current_path = reader.cells(cell_count)?;
+        let remaining_cells = reader.cells(cell_count)?;
+        let state = match reader.byte()? {
+            0 => WilsonState::PickingStart,
+            // La marche repart de la dernière cellule du chemin
+            1 if !current_path.is_empty() => WilsonState::Walking,
+            2 => WilsonState::CarvingPath,
+            3 => WilsonState::Finished,
+            _ => return Err(DecodeError::InvalidState),
+        };
+
+        let mut generator = Self {
+            rng,
+            in_maze: vec![true; cell_count],
+            current_path,
+            path_index: vec![ABSENT; cell_count],
+            remaining_cells,
+            remaining_index: vec![ABSENT; cell_count],
+            state,
+            layer_events: None,
+        };
 
-    fn step_picking_start(&mut self, _grid: &MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+        // Chaque cellule apparaît au plus une fois dans chaque liste
+        for (position, &cell) in generator.current_path.iter().enumerate() {
+            if std::mem::replace(&mut generator.path_index[cell], position) != ABSENT {
+                return Err(DecodeError::InvalidState);
+            }
+        }
+        for (position, &cell) in generator.remaining_cells.iter().enumerate() {
+            if std::mem::replace(&mut generator.remaining_index[cell], position) != ABSENT {
+                return Err(DecodeError::InvalidState);
+            }
+            generator.in_maze[cell] = false;
+        }
+        Ok(generator)
+    }
+
+    fn step_picking_start<G: Topology>(&mut self) -> (GenerationResult, Vec<G::Change>) {
         // Si toutes les cellules sont dans le labyrinthe, terminé
         if self.remaining_cells.is_empty() {
             self.state = WilsonState::Finished;
-            self.current_path.clear();
+            self.truncate_path(0);
             return (GenerationResult::Finished, Vec::new());
         }
 
         // Choisir une cellule aléatoire parmi celles pas encore dans le labyrinthe
-        let mut rng = rand::rng();
-        let start_index = rng.random_range(0..self.remaining_cells.len());
-        let start_point = self.remaining_cells[start_index];
+        let start_index = self.rng.random_range(0..self.remaining_cells.len());
+        let start_cell = self.remaining_cells[start_index];
 
-        // Démarrer un nouveau chemin avec ce point
-        self.current_path = vec![start_point];
+        // Démarrer un nouveau chemin avec cette cellule
+        self.truncate_path(0);
+        self.push_path(start_cell);
 
         // Passer à l'état marche
         self.state = WilsonState::Walking;
@@ -94,36 +183,35 @@ impl WilsonGenerator {
         (GenerationResult::Continue, Vec::new())
     }
 
-    fn step_walking(&mut self, grid: &MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+    fn step_walking<G: Topology>(&mut self, grid: &G) -> (GenerationResult, Vec<G::Change>) {
         // La dernière cellule du chemin est la position actuelle
         let current = *self.current_path.last().unwrap();
-        let current_index = grid.get_index(current.x, current.y);
 
         // Vérifier si on a atteint le labyrinthe
-        if self.in_maze[current_index] {
+        if self.in_maze[current] {
             // On a atteint le labyrinthe, passer à l'état "carving"
             self.state = WilsonState::CarvingPath;
             return (GenerationResult::Continue, Vec::new());
         }
 
         // Choisir une direction aléatoire
-        let neighbors = self.get_valid_neighbors(grid, current);
-        let mut rng = rand::rng();
-        let next_point = neighbors[rng.random_range(0..neighbors.len())];
+        let neighbors = grid.neighbors(current);
+        let next_cell = neighbors[self.rng.random_range(0..neighbors.len())];
 
-        // Vérifier si on crée une boucle (next_point est déjà dans le chemin)
-        if let Some(loop_index) = self.current_path.iter().position(|&p| p == next_point) {
+        // Vérifier si on crée une boucle (next_cell est déjà dans le chemin)
+        let loop_index = self.path_index[next_cell];
+        if loop_index != ABSENT {
             // Boucle détectée : garder seulement le chemin jusqu'à loop_index (inclus)
-            self.current_path.truncate(loop_index + 1);
+            self.truncate_path(loop_index + 1);
         } else {
-            // Pas de boucle : ajouter le nouveau point au chemin
-            self.current_path.push(next_point);
+            // Pas de boucle : ajouter la nouvelle cellule au chemin
+            self.push_path(next_cell);
         }
 
         (GenerationResult::Continue, Vec::new())
     }
 
-    fn step_carving_path(&mut self, grid: &mut MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+    fn step_carving_path<G: Topology>(&mut self, grid: &mut G) -> (GenerationResult, Vec<G::Change>) {
         // Le chemin ne devrait pas être vide ici
         if self.current_path.is_empty() {
             self.state = WilsonState::PickingStart;
@@ -132,88 +220,71 @@ impl WilsonGenerator {
 
         // Prendre la dernière cellule du chemin (fin de la pile)
         let current = *self.current_path.last().unwrap();
-        let current_index = grid.get_index(current.x, current.y);
 
-        // Ajouter la cellule courante au labyrinthe
-        self.in_maze[current_index] = true;
+        // Ajouter la cellule courante au labyrinthe (la première l'est déjà : c'est celle que la marche a atteinte)
+        if !self.in_maze[current] {
+            self.in_maze[current] = true;
+            self.record(1, current, true);
+        }
 
         // Retirer la cellule de remaining_cells
-        if let Some(pos) = self.remaining_cells.iter().position(|p| *p == current) {
-            self.remaining_cells.swap_remove(pos);
-        }
+        self.remove_remaining(current);
 
         // S'il n'y a qu'une cellule dans le chemin, on a fini
         if self.current_path.len() == 1 {
-            self.current_path.clear();
+            self.truncate_path(0);
             self.state = WilsonState::PickingStart;
             return (GenerationResult::Continue, Vec::new());
         }
 
         // Sinon, supprimer le mur entre current et la cellule précédente (avant-dernière)
         let next = self.current_path[self.current_path.len() - 2];
-
-        // Déterminer quel mur supprimer
-        let wall_change = if next.x > current.x {
-            // Mur vertical à droite de current
-            grid.remove_vertical_wall(current.x, current.y);
-            WallChange { x: current.x, y: current.y, wall_type: WallType::Vertical }
-        } else if next.x < current.x {
-            // Mur vertical à gauche de current (= à droite de next)
-            grid.remove_vertical_wall(next.x, next.y);
-            WallChange { x: next.x, y: next.y, wall_type: WallType::Vertical }
-        } else if next.y > current.y {
-            // Mur horizontal en bas de current
-            grid.remove_horizontal_wall(current.x, current.y);
-            WallChange { x: current.x, y: current.y, wall_type: WallType::Horizontal }
-        } else {
-            // Mur horizontal en haut de current (= en bas de next)
-            grid.remove_horizontal_wall(next.x, next.y);
-            WallChange { x: next.x, y: next.y, wall_type: WallType::Horizontal }
-        };
+        let wall_changes = grid.link(current, next).into_iter().collect();
 
         // Retirer la dernière cellule du chemin
-        self.current_path.pop();
+        self.truncate_path(self.current_path.len() - 1);
 
-        (GenerationResult::Continue, vec![wall_change])
+        (GenerationResult::Continue, wall_changes)
     }
 }
 
-impl GenerationAlgorithm for WilsonGenerator {
-    fn start(&mut self, grid: &mut MazeGrid) {
-        self.width = grid.width;
-        self.height = grid.height;
-        let total_cells = self.width * self.height;
+impl<G: Topology> GenerationAlgorithm<G> for WilsonGenerator {
+    fn start(&mut self, grid: &mut G) {
+        let total_cells = grid.cell_count();
 
         // Initialiser les états
         self.in_maze = vec![false; total_cells];
         self.current_path = Vec::new();
+        self.path_index = vec![ABSENT; total_cells];
+        // Les layers repartent de zéro : l'appelant doit reconstruire son état au prochain appel
+        self.layer_events = None;
 
         // Remplir la grille de murs
-        grid.fill_grid();
+        grid.fill_walls();
 
         // Créer la liste de toutes les cellules
-        self.remaining_cells = Vec::new();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                self.remaining_cells.push(Point { x, y });
-            }
+        self.remaining_cells = (0..total_cells).collect();
+        self.remaining_index = (0..total_cells).collect();
+
+        // Topologie vide : rien à creuser
+        if total_cells == 0 {
+            self.state = WilsonState::Finished;
+            return;
         }
 
         // Choisir une cellule aléatoire comme point de départ du labyrinthe
-        let mut rng = rand::rng();
-        let start_index = rng.random_range(0..self.remaining_cells.len());
-        let start_point = self.remaining_cells.swap_remove(start_index);
-
-        let maze_start_index = grid.get_index(start_point.x, start_point.y);
-        self.in_maze[maze_start_index] = true;
+        let start_index = self.rng.random_range(0..self.remaining_cells.len());
+        let start_cell = self.remaining_cells[start_index];
+        self.remove_remaining(start_cell);
+        self.in_maze[start_cell] = true;
 
         // Commencer avec l'état "choisir une nouvelle cellule"
         self.state = WilsonState::PickingStart;
     }
 
-    fn step(&mut self, grid: &mut MazeGrid) -> (GenerationResult, Vec<WallChange>) {
+    fn step(&mut self, grid: &mut G) -> (GenerationResult, Vec<G::Change>) {
         match self.state {
-            WilsonState::PickingStart => self.step_picking_start(grid),
+            WilsonState::PickingStart => self.step_picking_start::<G>(),
             WilsonState::Walking => self.step_walking(grid),
             WilsonState::CarvingPath => self.step_carving_path(grid),
             WilsonState::Finished => (GenerationResult::Finished, Vec::new()),
@@ -228,21 +299,41 @@ impl GenerationAlgorithm for WilsonGenerator {
         "Wilson's Algorithm"
     }
 
-    fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+    // Cellules dans le labyrinthe ; les premières marches sont longues, l'avancement accélère ensuite
+    fn progress(&self) -> f64 {
+        if self.state == WilsonState::Finished {
+            1.0
+        } else {
+            ratio(self.in_maze.len() - self.remaining_cells.len(), self.in_maze.len())
+        }
+    }
+
+    fn take_layer_deltas(&mut self, grid: &G) -> Option<Vec<LayerDelta>> {
+        let Some(events) = &mut self.layer_events else {
+            // Premier appel : l'appelant compare les layers complets, puis les événements prennent le relais
+            self.layer_events = Some(Vec::new());
+            return None;
+        };
+
+        let deltas = events
+            .drain(..)
+            .map(|(layer, cell, entered)| LayerDelta { layer, point: grid.cell_point(cell), entered })
+            .collect();
+        Some(deltas)
+    }
+
+    fn get_cell_layers(&self, grid: &G) -> Vec<Vec<Point>> {
         let mut layers = Vec::new();
 
 
         // Layer 1 : Le chemin de la marche aléatoire (bleu)
-        layers.push(self.current_path.clone());
+        layers.push(self.current_path.iter().map(|&cell| grid.cell_point(cell)).collect());
         
         // Layer 0 : Cellules dans le labyrinthe (vert - le "but")
         let mut maze_cells = Vec::new();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let index = y * self.width + x;
-                if self.in_maze[index] {
-                    maze_cells.push(Point { x, y });
-                }
+        for cell in 0..self.in_maze.len() {
+            if self.in_maze[cell] {
+                maze_cells.push(grid.cell_point(cell));
             }
         }
         layers.push(maze_cells);
@@ -0,0 +1,191 @@
+use crate::Point;
+use crate::maze_grid::{Crossing, MazeGrid};
+use crate::topology::Topology;
+
+/// Segment de mur exprimé en unités de cellule
+/// L'origine est le coin supérieur gauche, l'axe y est orienté vers le bas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+}
+
+/// Mur courbe : arc de cercle parcouru dans le sens des angles croissants
+/// Les angles sont en radians ; l'axe y étant orienté vers le bas, ce sens est horaire à l'écran
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircularArc {
+    pub cx: f64,
+    pub cy: f64,
+    pub radius: f64,
+    pub start_angle: f64,
+    pub end_angle: f64,
+}
+
+impl CircularArc {
+    /// Point de l'arc situé à l'angle donné
+    pub fn point_at(&self, angle: f64) -> (f64, f64) {
+        (self.cx + self.radius * angle.cos(), self.cy + self.radius * angle.sin())
+    }
+}
+
+/// Sens d'un escalier partant d'une cellule (grilles à plusieurs étages)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stairs {
+    Up,
+    Down,
+    /// Escaliers vers le haut et vers le bas dans la même cellule
+    Both,
+}
+
+/// Escalier à dessiner au centre d'une cellule
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StairMarker {
+    pub cx: f64,
+    pub cy: f64,
+    pub stairs: Stairs,
+}
+
+impl StairMarker {
+    /// Triangles pleins du marqueur, en unités de cellule :
+    /// pointe en haut pour monter, en bas pour descendre, les deux empilés sinon
+    pub fn triangles(&self) -> Vec<[(f64, f64); 3]> {
+        let (cx, cy) = (self.cx, self.cy);
+        let up = |cy: f64, size: f64| [(cx, cy - size), (cx + size, cy + size), (cx - size, cy + size)];
+        let down = |cy: f64, size: f64| [(cx, cy + size), (cx - size, cy - size), (cx + size, cy - size)];
+
+        match self.stairs {
+            Stairs::Up => vec![up(cy, 0.25)],
+            Stairs::Down => vec![down(cy, 0.25)],
+            Stairs::Both => vec![up(cy - 0.15, 0.12), down(cy + 0.15, 0.12)],
+        }
+    }
+}
+
+/// Géométrie de rendu d'une grille, utilisée par les exports (SVG, PDF)
+pub trait MazeGeometry {
+    /// Taille du dessin (largeur, hauteur) en unités de cellule
+    fn size(&self) -> (f64, f64);
+
+    /// Tous les murs à dessiner, bordure comprise
+    fn wall_segments(&self) -> Vec<Segment>;
+
+    /// Murs courbes (grilles polaires) ; aucun par défaut
+    fn wall_arcs(&self) -> Vec<CircularArc> {
+        Vec::new()
+    }
+
+    /// Centre d'une cellule, en unités de cellule
+    fn cell_center(&self, cell: usize) -> (f64, f64);
+
+    /// Escaliers vers les autres étages ; aucun par défaut
+    fn stair_markers(&self) -> Vec<StairMarker> {
+        Vec::new()
+    }
+}
+
+/// Retrait des murs du couloir supérieur d'un croisement, en fraction de cellule
+const CROSSING_INSET: f64 = 0.2;
+
+/// Murs d'un croisement : les deux bords du couloir supérieur, et les amorces
+/// qui relient le tunnel au bord de la cellule
+fn crossing_segments(point: Point, crossing: Crossing) -> Vec<Segment> {
+    let (x, y) = (point.x as f64, point.y as f64);
+    let (near, far) = (CROSSING_INSET, 1.0 - CROSSING_INSET);
+
+    // Segments d'un croisement dont le tunnel est horizontal ; transposés sinon
+    let horizontal = [
+        (near, 0.0, near, 1.0),
+        (far, 0.0, far, 1.0),
+        (0.0, 0.0, near, 0.0),
+        (far, 0.0, 1.0, 0.0),
+        (0.0, 1.0, near, 1.0),
+        (far, 1.0, 1.0, 1.0),
+    ];
+
+    horizontal
+        .into_iter()
+        .map(|(x1, y1, x2, y2)| match crossing {
+            Crossing::Horizontal => Segment { x1: x + x1, y1: y + y1, x2: x + x2, y2: y + y2 },
+            Crossing::Vertical => Segment { x1: x + y1, y1: y + x1, x2: x + y2, y2: y + x2 },
+        })
+        .collect()
+}
+
+impl MazeGeometry for MazeGrid {
+    fn size(&self) -> (f64, f64) {
+        (self.width as f64, self.height as f64)
+    }
+
+    /// Les segments colinéaires consécutifs sont fusionnés pour alléger les exports
+    /// En mode torique, les murs de raccord sont dessinés sur les deux bords opposés
+    fn wall_segments(&self) -> Vec<Segment> {
+        let width = self.width as f64;
+        let height = self.height as f64;
+        let mut segments = Vec::new();
+
+        // Bordure extérieure : des murs pleins, sauf pour les bords raccordés
+        if !self.wraps_vertically() {
+            segments.push(Segment { x1: 0.0, y1: 0.0, x2: width, y2: 0.0 });
+            segments.push(Segment { x1: 0.0, y1: height, x2: width, y2: height });
+        }
+        if !self.wraps_horizontally() {
+            segments.push(Segment { x1: width, y1: 0.0, x2: width, y2: height });
+            segments.push(Segment { x1: 0.0, y1: 0.0, x2: 0.0, y2: height });
+        }
+
+        // Murs horizontaux (en bas de chaque cellule), fusionnés ligne par ligne
+        let rows = if self.wraps_vertically() { self.height } else { self.height.saturating_sub(1) };
+        for y in 0..rows {
+            let mut run_start = None;
+            for x in 0..=self.width {
+                let wall = x < self.width && self.horizontal_walls.get(self.get_index(x, y));
+                match (wall, run_start) {
+                    (true, None) => run_start = Some(x),
+                    (false, Some(start)) => {
+                        let line_y = (y + 1) as f64;
+                        segments.push(Segment { x1: start as f64, y1: line_y, x2: x as f64, y2: line_y });
+                        if y + 1 == self.height {
+                            segments.push(Segment { x1: start as f64, y1: 0.0, x2: x as f64, y2: 0.0 });
+                        }
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Murs verticaux (à droite de chaque cellule), fusionnés colonne par colonne
+        let columns = if self.wraps_horizontally() { self.width } else { self.width.saturating_sub(1) };
+        for x in 0..columns {
+            let mut run_start = None;
+            for y in 0..=self.height {
+                let wall = y < self.height && self.vertical_walls.get(self.get_index(x, y));
+                match (wall, run_start) {
+                    (true, None) => run_start = Some(y),
+                    (false, Some(start)) => {
+                        let line_x = (x + 1) as f64;
+                        segments.push(Segment { x1: line_x, y1: start as f64, x2: line_x, y2: y as f64 });
+                        if x + 1 == self.width {
+                            segments.push(Segment { x1: 0.0, y1: start as f64, x2: 0.0, y2: y as f64 });
+                        }
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Croisements : le couloir du dessus est bordé de murs en retrait qui masquent le tunnel
+        for (&cell, &crossing) in &self.crossings {
+            segments.extend(crossing_segments(self.cell_point(cell), crossing));
+        }
+
+        segments
+    }
+
+    fn cell_center(&self, cell: usize) -> (f64, f64) {
+        ((cell % self.width) as f64 + 0.5, (cell / self.width) as f64 + 0.5)
+    }
+}
@@ -0,0 +1,213 @@
+use crate::generators::WallChange;
+use crate::maze_grid::{GridEdit, MazeGrid};
+
+/// Historique des modifications de la grille, pour annuler / rétablir
+///
+/// Chaque étape regroupe les modifications d'une opération : un pas de génération,
+/// une édition manuelle, un remplissage ou un nettoyage de la grille
+#[derive(Default)]
+pub(crate) struct History {
+    steps: Vec<Vec<GridEdit>>,
+    // Nombre d'étapes actuellement appliquées à la grille
+    position: usize,
+}
+
+impl History {
+    /// Ajoute une étape ; les étapes annulées qui la suivaient sont abandonnées
+    pub(crate) fn record(&mut self, edits: Vec<GridEdit>) {
+        if edits.is_empty() {
+            return;
+        }
+
+        self.steps.truncate(self.position);
+        self.steps.push(edits);
+        self.position += 1;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Annule la dernière étape appliquée et retourne les murs modifiés
+    pub(crate) fn undo(&mut self, grid: &mut MazeGrid) -> Vec<WallChange> {
+        if self.position == 0 {
+            return Vec::new();
+        }
+
+        self.position -= 1;
+        // Les modifications sont défaites dans l'ordre inverse
+        self.steps[self.position].iter().rev().flat_map(|edit| grid.apply_edit(edit, false)).collect()
+    }
+
+    /// Rétablit l'étape suivante et retourne les murs modifiés
+    pub(crate) fn redo(&mut self, grid: &mut MazeGrid) -> Vec<WallChange> {
+        if self.position == self.steps.len() {
+            return Vec::new();
+        }
+
+        self.position += 1;
+        self.steps[self.position - 1].iter().flat_map(|edit| grid.apply_edit(edit, true)).collect()
+    }
+
+    /// Se place après `step` étapes (borné à la longueur de l'historique)
+    pub(crate) fn seek(&mut self, grid: &mut MazeGrid, step: usize) -> Vec<WallChange> {
+        let target = step.min(self.steps.len());
+        let mut wall_changes = Vec::new();
+
+        while self.position > target {
+            wall_changes.extend(self.undo(grid));
+        }
+        while self.position < target {
+            wall_changes.extend(self.redo(grid));
+        }
+
+        wall_changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+    use crate::generators::{WallOp, WallType};
+    use crate::maze_grid::Crossing;
+
+    // Exécute une opération sur la grille avec le journal actif et l'enregistre comme une étape
+    fn record(history: &mut History, grid: &mut MazeGrid, operation: impl FnOnce(&mut MazeGrid)) {
+        grid.journal = Some(Vec::new());
+        operation(grid);
+        history.record(grid.journal.take().unwrap());
+    }
+
+    fn snapshot(grid: &MazeGrid) -> Vec<u8> {
+        crate::export::to_bytes(grid)
+    }
+
+    #[test]
+    fn undo_and_redo_restore_each_step() {
+        let mut grid = MazeGrid::new(3, 3);
+        let mut history = History::default();
+        let initial = snapshot(&grid);
+
+        record(&mut history, &mut grid, |grid| {
+            grid.set_wall(0, 0, WallType::Vertical, true);
+            grid.set_wall(1, 1, WallType::Horizontal, true);
+        });
+        let after_first = snapshot(&grid);
+        record(&mut history, &mut grid, |grid| grid.fill_grid());
+        let after_second = snapshot(&grid);
+        assert_eq!((history.len(), history.position()), (2, 2));
+
+        // Une opération sans effet n'ajoute pas d'étape
+        record(&mut history, &mut grid, |grid| grid.fill_grid());
+        assert_eq!(history.len(), 2);
+
+        assert_eq!(history.undo(&mut grid).len(), 18 - 2);
+        assert_eq!(snapshot(&grid), after_first);
+        let changes = history.undo(&mut grid);
+        assert_eq!(snapshot(&grid), initial);
+        // Défaits dans l'ordre inverse
+        assert_eq!(
+            changes,
+            vec![
+                WallChange { x: 1, y: 1, wall_type: WallType::Horizontal, op: WallOp::Removed },
+                WallChange { x: 0, y: 0, wall_type: WallType::Vertical, op: WallOp::Removed },
+            ]
+        );
+        assert!(history.undo(&mut grid).is_empty());
+        assert_eq!(history.position(), 0);
+
+        history.redo(&mut grid);
+        history.redo(&mut grid);
+        assert_eq!(snapshot(&grid), after_second);
+        assert!(history.redo(&mut grid).is_empty());
+        assert_eq!(history.position(), 2);
+    }
+
+    #[test]
+    fn recording_after_undo_drops_the_undone_steps() {
+        let mut grid = MazeGrid::new(3, 3);
+        let mut history = History::default();
+        record(&mut history, &mut grid, |grid| grid.add_vertical_wall(0, 0));
+        record(&mut history, &mut grid, |grid| grid.remove_vertical_wall(0, 0));
+        record(&mut history, &mut grid, |grid| grid.add_horizontal_wall(2, 0));
+
+        history.undo(&mut grid);
+        history.undo(&mut grid);
+        record(&mut history, &mut grid, |grid| grid.add_horizontal_wall(1, 1));
+        assert_eq!((history.len(), history.position()), (2, 2));
+        assert!(history.redo(&mut grid).is_empty());
+        assert!(grid.has_wall(0, 0, WallType::Vertical) && grid.has_wall(1, 1, WallType::Horizontal));
+        assert!(!grid.has_wall(2, 0, WallType::Horizontal));
+    }
+
+    #[test]
+    fn seek_moves_in_both_directions_and_is_bounded() {
+        let mut grid = MazeGrid::new(4, 1);
+        let mut history = History::default();
+        let mut states = vec![snapshot(&grid)];
+        for x in 0..3 {
+            record(&mut history, &mut grid, |grid| grid.add_vertical_wall(x, 0));
+            states.push(snapshot(&grid));
+        }
+
+        history.seek(&mut grid, 1);
+        assert_eq!((history.position(), snapshot(&grid)), (1, states[1].clone()));
+        let changes = history.seek(&mut grid, 10);
+        assert_eq!((history.position(), snapshot(&grid)), (3, states[3].clone()));
+        assert_eq!(changes.len(), 2);
+        history.seek(&mut grid, 0);
+        assert_eq!(snapshot(&grid), states[0]);
+    }
+
+    #[test]
+    fn crossings_are_undone_with_their_walls() {
+        let mut grid = MazeGrid::new(3, 3);
+        grid.fill_grid();
+        grid.carve_passage(Point::new(1, 0), Point::new(1, 1));
+        grid.carve_passage(Point::new(1, 1), Point::new(1, 2));
+        let mut history = History::default();
+
+        record(&mut history, &mut grid, |grid| {
+            grid.tunnel_under(Point::new(0, 1), Point::new(2, 1));
+        });
+        assert_eq!(grid.crossing(1, 1), Some(Crossing::Horizontal));
+
+        assert_eq!(history.undo(&mut grid).len(), 2);
+        assert_eq!(grid.crossing(1, 1), None);
+        assert!(grid.has_wall(0, 1, WallType::Vertical));
+
+        history.redo(&mut grid);
+        assert_eq!(grid.crossing(1, 1), Some(Crossing::Horizontal));
+    }
+
+    #[test]
+    fn fill_and_clear_are_journaled_as_one_edit() {
+        let mut grid = MazeGrid::new(3, 3);
+        grid.fill_grid();
+        grid.carve_passage(Point::new(1, 0), Point::new(1, 1));
+        grid.carve_passage(Point::new(1, 1), Point::new(1, 2));
+        grid.tunnel_under(Point::new(0, 1), Point::new(2, 1));
+        let before = snapshot(&grid);
+        let mut history = History::default();
+
+        record(&mut history, &mut grid, |grid| grid.clear_grid());
+        assert_eq!(history.steps[0].len(), 1);
+        assert!(matches!(history.steps[0][0], GridEdit::Reset(_)));
+        let cleared = snapshot(&grid);
+
+        // L'annulation rend les murs et le croisement, et retourne chaque mur modifié
+        let changes = history.undo(&mut grid);
+        assert_eq!(snapshot(&grid), before);
+        assert_eq!(grid.crossing(1, 1), Some(Crossing::Horizontal));
+        assert!(changes.iter().all(|change| change.op == WallOp::Added));
+        assert_eq!(changes.len(), 18 - 4);
+
+        assert_eq!(history.redo(&mut grid), changes.iter().map(|change| change.inverse()).collect::<Vec<_>>());
+        assert_eq!(snapshot(&grid), cleared);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::Point;
+use crate::generators::LayerDelta;
+use std::collections::HashSet;
+
+/// Ce que le consommateur des changements de layers connaît déjà
+#[derive(Default)]
+pub(crate) enum LayerTracker {
+    /// Rien n'a été transmis, ou l'état transmis est perdu : il faut tout renvoyer
+    #[default]
+    Unknown,
+    /// Layers transmis lors du dernier appel, pour les comparer aux suivants
+    Snapshot(Vec<HashSet<Point>>),
+    /// L'algorithme transmet lui-même ses changements : aucune copie n'est gardée
+    Tracked,
+}
+
+impl LayerTracker {
+    /// Changements depuis l'appel précédent ; le booléen indique qu'il faut d'abord vider les layers
+    /// `deltas` vient de l'algorithme s'il suit ses layers, `layers` sert à la comparaison sinon
+    pub(crate) fn update(
+        &mut self,
+        deltas: Option<Vec<LayerDelta>>,
+        layers: impl FnOnce() -> Vec<Vec<Point>>,
+    ) -> (bool, Vec<LayerDelta>) {
+        let state = std::mem::take(self);
+
+        // Les changements fournis par l'algorithme partent de l'état transmis au dernier appel,
+        // ou de layers vides si rien n'a encore été transmis
+        if let Some(deltas) = deltas {
+            *self = Self::Tracked;
+            return (matches!(state, Self::Unknown), deltas);
+        }
+
+        let previous = match state {
+            Self::Snapshot(previous) => Some(previous),
+            // Suivi interrompu par l'algorithme : l'état transmis ne peut plus être comparé
+            Self::Unknown | Self::Tracked => None,
+        };
+
+        let layers: Vec<HashSet<Point>> = layers().into_iter().map(|layer| layer.into_iter().collect()).collect();
+        let reset = previous.is_none();
+        let previous = previous.unwrap_or_default();
+
+        let mut changes = Vec::new();
+        for (layer, cells) in previous.iter().enumerate() {
+            let current = layers.get(layer);
+            changes.extend(
+                cells
+                    .iter()
+                    .filter(|point| current.is_none_or(|current| !current.contains(point)))
+                    .map(|&point| LayerDelta { layer, point, entered: false }),
+            );
+        }
+        for (layer, cells) in layers.iter().enumerate() {
+            let before = previous.get(layer);
+            changes.extend(
+                cells
+                    .iter()
+                    .filter(|point| before.is_none_or(|before| !before.contains(point)))
+                    .map(|&point| LayerDelta { layer, point, entered: true }),
+            );
+        }
+
+        *self = Self::Snapshot(layers);
+        (reset, changes)
+    }
+}
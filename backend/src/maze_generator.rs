@@ -0,0 +1,441 @@
+use crate::Point;
+use crate::dirty::DirtyTile;
+use crate::error::MazeError;
+use crate::generators::{Algorithm, AlgorithmKind, GenerationAlgorithm, GenerationResult, LayerDelta, WallChange, WallType};
+use crate::history::History;
+use crate::layers::LayerTracker;
+use crate::maze_grid::MazeGrid;
+use crate::replay::{Frame, Recording};
+use crate::snapshot;
+
+/// Dimension maximale (largeur ou hauteur) acceptée par le générateur
+/// Les murs tiennent en bits, mais l'état par cellule des générateurs (cellules visitées,
+/// index de Wilson, parents de Kruskal...) doit aussi tenir dans les 4 Go adressables en wasm32
+pub const MAX_DIMENSION: usize = 20000;
+
+// Notre générateur de labyrinthe avec système de cloisons
+// API Rust pure : la couche WASM (feature "wasm") est construite par-dessus
+pub struct MazeGenerator {
+    grid: MazeGrid,
+    // Génération en cours ; None tant qu'aucune n'a été démarrée, ou après un redimensionnement
+    active_generator: Option<Algorithm>,
+    // Historique annuler / rétablir, désactivé par défaut
+    history: Option<History>,
+    // Enregistrement en cours, pour une relecture ultérieure
+    recording: Option<Recording>,
+    // Layers déjà transmis par take_layer_deltas
+    layer_tracker: LayerTracker,
+}
+
+impl MazeGenerator {
+    pub fn new(width: usize, height: usize) -> Result<MazeGenerator, MazeError> {
+        Self::check_dimensions(width, height)?;
+
+        Ok(MazeGenerator {
+            grid: MazeGrid::new(width, height),
+            active_generator: None,
+            history: None,
+            recording: None,
+            layer_tracker: LayerTracker::default(),
+        })
+    }
+
+    fn check_dimensions(width: usize, height: usize) -> Result<(), MazeError> {
+        if width > 0 && height > 0 && width <= MAX_DIMENSION && height <= MAX_DIMENSION {
+            Ok(())
+        } else {
+            Err(MazeError::InvalidDimensions { width, height })
+        }
+    }
+
+    // Accès en lecture à la grille (export, résolution...)
+    pub fn grid(&self) -> &MazeGrid {
+        &self.grid
+    }
+
+    // Vérifier s'il y a un mur vertical
+    pub fn has_vertical_wall(&self, x: usize, y: usize) -> bool {
+        if self.grid.is_valid_point(x, y) {
+            let index = self.grid.get_index(x, y);
+            self.grid.vertical_walls.get(index)
+        } else {
+            false
+        }
+    }
+
+    // Vérifier s'il y a un mur horizontal
+    pub fn has_horizontal_wall(&self, x: usize, y: usize) -> bool {
+        if self.grid.is_valid_point(x, y) {
+            let index = self.grid.get_index(x, y);
+            self.grid.horizontal_walls.get(index)
+        } else {
+            false
+        }
+    }
+
+    // Nettoyer la grille (supprimer tous les murs)
+    pub fn clear_grid(&mut self) {
+        self.abandon_recording();
+        self.begin_record();
+        self.grid.clear_grid();
+        self.end_record();
+    }
+
+    // Remplir la grille de murs (labyrinthe plein)
+    pub fn fill_grid(&mut self) {
+        self.abandon_recording();
+        self.begin_record();
+        self.grid.fill_grid();
+        self.end_record();
+    }
+
+    // Placer ou retirer un mur à la main (éditeur)
+    // Retourne None si le mur est sur la bordure ou déjà dans cet état
+    pub fn set_wall(&mut self, x: usize, y: usize, wall_type: WallType, present: bool) -> Result<Option<WallChange>, MazeError> {
+        self.grid.check_point(x, y)?;
+
+        self.begin_record();
+        let wall_change = self.grid.set_wall(x, y, wall_type, present);
+        self.end_record();
+        if wall_change.is_some() {
+            self.abandon_recording();
+        }
+        Ok(wall_change)
+    }
+
+    // Appliquer des changements reçus d'ailleurs (synchronisation réseau, `MazeGrid::diff`...)
+    // Retourne ceux qui ont effectivement modifié la grille ; rien n'est appliqué si l'un d'eux est hors de la grille
+    pub fn apply_changes(&mut self, changes: &[WallChange]) -> Result<Vec<WallChange>, MazeError> {
+        for change in changes {
+            self.grid.check_point(change.x, change.y)?;
+        }
+
+        self.begin_record();
+        let applied: Vec<WallChange> = changes.iter().copied().filter(|change| self.grid.apply_change(*change)).collect();
+        self.end_record();
+        if !applied.is_empty() {
+            self.abandon_recording();
+        }
+        Ok(applied)
+    }
+
+    // Générer un labyrinthe complet instantanément
+    pub fn generate_maze(&mut self, algorithm: AlgorithmKind) {
+        self.start_generation(algorithm);
+        while !self.is_finished() {
+            self.step_active();
+        }
+    }
+
+    // Méthode unifiée pour démarrer la génération avec animation
+    pub fn start_generation(&mut self, algorithm: AlgorithmKind) {
+        self.start_with(Algorithm::new(algorithm));
+    }
+
+    // Démarrer une génération reproductible à partir d'une graine
+    pub fn start_generation_with_seed(&mut self, algorithm: AlgorithmKind, seed: u64) {
+        self.start_with(Algorithm::with_seed(algorithm, seed));
+    }
+
+    // Démarrer un labyrinthe tissé, avec ou sans graine
+    // Retourne false, sans rien modifier, si l'algorithme ne sait pas tisser
+    pub fn start_weave_generation(&mut self, algorithm: AlgorithmKind, seed: Option<u64>) -> bool {
+        let generator = match seed {
+            Some(seed) => Algorithm::with_seed(algorithm, seed),
+            None => Algorithm::new(algorithm),
+        };
+        let Some(generator) = generator.weave(true) else {
+            return false;
+        };
+
+        self.start_with(generator);
+        true
+    }
+
+    // Démarrer la génération avec un algorithme fourni par l'appelant (ex: générateur externe)
+    pub fn start_custom_generation(&mut self, generator: Box<dyn GenerationAlgorithm>) {
+        self.start_with(Algorithm::Custom(generator));
+    }
+
+    fn start_with(&mut self, mut generator: Algorithm) {
+        self.abandon_recording();
+        self.layer_tracker = LayerTracker::default();
+        self.begin_record();
+        generator.start(&mut self.grid);
+        self.end_record();
+        self.active_generator = Some(generator);
+    }
+
+    // Effectuer une étape de génération et retourner les murs modifiés
+    pub fn generation_step(&mut self) -> Result<(GenerationResult, Vec<WallChange>), MazeError> {
+        self.check_started()?;
+        Ok(self.step_active())
+    }
+
+    // Effectuer jusqu'à `steps` étapes, en s'arrêtant à la fin de la génération
+    pub fn generation_steps(&mut self, steps: usize) -> Result<(GenerationResult, Vec<WallChange>), MazeError> {
+        self.check_started()?;
+
+        let mut wall_changes = Vec::new();
+        for _ in 0..steps {
+            let (result, changes) = self.step_active();
+            wall_changes.extend(changes);
+            if result == GenerationResult::Finished {
+                return Ok((result, wall_changes));
+            }
+        }
+
+        Ok((GenerationResult::Continue, wall_changes))
+    }
+
+    // Enchaîner les étapes jusqu'à avoir au moins `max_changes` murs modifiés (budget d'une image)
+    // Une étape peut dépasser le budget : Recursive Division ajoute un mur entier d'un coup
+    pub fn generation_run_for(&mut self, max_changes: usize) -> Result<(GenerationResult, Vec<WallChange>), MazeError> {
+        self.check_started()?;
+
+        let mut wall_changes = Vec::new();
+        loop {
+            let (result, changes) = self.step_active();
+            wall_changes.extend(changes);
+            if result == GenerationResult::Finished || wall_changes.len() >= max_changes {
+                return Ok((result, wall_changes));
+            }
+        }
+    }
+
+    fn check_started(&self) -> Result<(), MazeError> {
+        if self.active_generator.is_some() { Ok(()) } else { Err(MazeError::NotStarted) }
+    }
+
+    // Étape de la génération en cours, déjà vérifiée par l'appelant
+    fn step_active(&mut self) -> (GenerationResult, Vec<WallChange>) {
+        self.begin_record();
+        let step = match &mut self.active_generator {
+            Some(generator) => generator.step(&mut self.grid),
+            None => (GenerationResult::Finished, Vec::new()),
+        };
+        self.end_record();
+
+        if let (Some(recording), Some(generator)) = (&mut self.recording, &self.active_generator) {
+            let changes = step.1.clone();
+            let layers = generator.get_cell_layers(&self.grid);
+            recording.push(Frame { result: step.0, changes, layers });
+        }
+
+        step
+    }
+
+    // Vérifier si la génération en cours est terminée (false si aucune n'a été démarrée)
+    pub fn is_finished(&self) -> bool {
+        self.active_generator.as_ref().is_some_and(Algorithm::is_finished)
+    }
+
+    // Avancement de la génération en cours, entre 0 et 1 (0 si aucune n'a été démarrée)
+    pub fn progress(&self) -> f64 {
+        self.active_generator.as_ref().map_or(0.0, Algorithm::progress)
+    }
+
+    // Temps restant estimé par extrapolation linéaire de l'avancement, dans l'unité de `elapsed`
+    // (temps déjà passé à générer) ; None tant que rien n'a avancé
+    // Wilson accélère en fin de génération : l'estimation est d'abord pessimiste
+    pub fn estimate_remaining(&self, elapsed: f64) -> Option<f64> {
+        let progress = self.progress();
+        (progress > 0.0).then(|| elapsed * (1.0 - progress) / progress)
+    }
+
+    // Type de l'algorithme actif (None sans génération, ou pour un algorithme externe)
+    pub fn algorithm_kind(&self) -> Option<AlgorithmKind> {
+        self.active_generator.as_ref().and_then(Algorithm::kind)
+    }
+
+    // Nom de l'algorithme actif, s'il y en a un
+    pub fn algorithm_name(&self) -> Option<&'static str> {
+        self.active_generator.as_ref().map(Algorithm::get_name)
+    }
+
+    // Obtenir les layers de cellules pour l'animation
+    pub fn get_cell_layers(&self) -> Vec<Vec<Point>> {
+        self.active_generator.as_ref().map(|generator| generator.get_cell_layers(&self.grid)).unwrap_or_default()
+    }
+
+    // Cellules entrées dans un layer ou sorties depuis l'appel précédent
+    // Si le booléen est vrai, les layers affichés doivent d'abord être vidés (nouvel algorithme...)
+    // Wilson fournit ses changements directement ; pour les autres, les layers sont comparés à l'appel précédent
+    pub fn take_layer_deltas(&mut self) -> (bool, Vec<LayerDelta>) {
+        let deltas = self.active_generator.as_mut().and_then(|generator| generator.take_layer_deltas(&self.grid));
+        let (generator, grid) = (&self.active_generator, &self.grid);
+        self.layer_tracker
+            .update(deltas, || generator.as_ref().map(|generator| generator.get_cell_layers(grid)).unwrap_or_default())
+    }
+
+    // Redimensionner la grille
+    // La génération en cours est abandonnée : son état ne correspond plus à la grille
+    pub fn resize_grid(&mut self, new_width: usize, new_height: usize) -> Result<(), MazeError> {
+        Self::check_dimensions(new_width, new_height)?;
+
+        self.grid.resize(new_width, new_height);
+        self.discard_generation_state();
+        Ok(())
+    }
+
+    // La grille a changé de forme : la génération en cours, l'enregistrement
+    // et les étapes de l'historique ne lui correspondent plus
+    fn discard_generation_state(&mut self) {
+        self.active_generator = None;
+        self.layer_tracker = LayerTracker::default();
+        self.recording = None;
+        if let Some(history) = &mut self.history {
+            *history = History::default();
+        }
+    }
+
+    // Instantané de la grille et de la génération en cours (pile, frontière, chemin, chambres...
+    // et position dans la suite aléatoire), à recharger avec `restore`, par exemple après un rechargement de la page
+    pub fn snapshot(&self) -> Result<Vec<u8>, MazeError> {
+        snapshot::encode(&self.grid, self.active_generator.as_ref())
+    }
+
+    // Recharger un instantané : la génération reprend exactement où elle s'était arrêtée
+    // Rien n'est modifié en cas d'erreur ; sinon l'historique est vidé et l'enregistrement en cours abandonné
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<(), MazeError> {
+        let (mut grid, generator) = snapshot::decode(bytes)?;
+
+        // Nouvelle grille : toutes les tuiles sont à redessiner
+        if let Some(dirty) = &self.grid.dirty {
+            grid.enable_dirty_tracking(dirty.tile_size());
+        }
+        self.grid = grid;
+        self.discard_generation_state();
+        self.active_generator = generator;
+        Ok(())
+    }
+
+    // Raccorder les bords opposés (labyrinthe cylindrique ou torique)
+    // Comme pour un redimensionnement, changer les voisinages abandonne la génération en cours,
+    // l'enregistrement et l'historique
+    pub fn set_wrap(&mut self, horizontal: bool, vertical: bool) {
+        let wraps = |grid: &MazeGrid| (grid.wraps_horizontally(), grid.wraps_vertically());
+        let before = wraps(&self.grid);
+        self.grid.set_wrap(horizontal, vertical);
+        if wraps(&self.grid) != before {
+            self.discard_generation_state();
+        }
+    }
+
+    // Obtenir les dimensions actuelles
+    pub fn get_grid_width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn get_grid_height(&self) -> usize {
+        self.grid.height
+    }
+
+    // Suivi des tuiles modifiées, pour un rendu incrémental
+    pub fn enable_dirty_tracking(&mut self, tile_size: usize) {
+        self.grid.enable_dirty_tracking(tile_size);
+    }
+
+    pub fn disable_dirty_tracking(&mut self) {
+        self.grid.disable_dirty_tracking();
+    }
+
+    // Tuiles modifiées depuis l'appel précédent
+    pub fn take_dirty_tiles(&mut self) -> Vec<DirtyTile> {
+        self.grid.take_dirty_tiles()
+    }
+
+    // Activer ou désactiver l'historique annuler / rétablir
+    // Chaque opération (pas de génération, édition, remplissage...) devient une étape ;
+    // l'activer sur une grande grille coûte de la mémoire à chaque remplissage complet
+    pub fn set_history_enabled(&mut self, enabled: bool) {
+        self.history = enabled.then(History::default);
+    }
+
+    // Annuler la dernière étape et retourner les murs modifiés
+    // Seule la grille est rembobinée : la génération en cours est abandonnée
+    pub fn undo(&mut self) -> Vec<WallChange> {
+        self.move_in_history(|history, grid| history.undo(grid))
+    }
+
+    // Rétablir l'étape annulée suivante
+    pub fn redo(&mut self) -> Vec<WallChange> {
+        self.move_in_history(|history, grid| history.redo(grid))
+    }
+
+    // Se placer après `step` étapes de l'historique, en avant ou en arrière
+    pub fn seek(&mut self, step: usize) -> Vec<WallChange> {
+        self.move_in_history(|history, grid| history.seek(grid, step))
+    }
+
+    // Déplacement dans l'historique ; si la grille a changé, l'état de l'algorithme en cours
+    // ne lui correspond plus (cellules visitées, frontière...) et la génération est abandonnée
+    fn move_in_history(&mut self, apply: impl FnOnce(&mut History, &mut MazeGrid) -> Vec<WallChange>) -> Vec<WallChange> {
+        let Some(history) = &mut self.history else {
+            return Vec::new();
+        };
+
+        let position = history.position();
+        let wall_changes = apply(history, &mut self.grid);
+        if history.position() != position {
+            self.active_generator = None;
+            self.layer_tracker = LayerTracker::default();
+            self.abandon_recording();
+        }
+        wall_changes
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history_position() > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history_position() < self.history_len()
+    }
+
+    // Nombre d'étapes enregistrées
+    pub fn history_len(&self) -> usize {
+        self.history.as_ref().map_or(0, History::len)
+    }
+
+    // Nombre d'étapes actuellement appliquées
+    pub fn history_position(&self) -> usize {
+        self.history.as_ref().map_or(0, History::position)
+    }
+
+    // Activer le journal de la grille le temps d'une opération
+    fn begin_record(&mut self) {
+        if self.history.is_some() {
+            self.grid.journal = Some(Vec::new());
+        }
+    }
+
+    // Enregistrer les modifications de l'opération comme une étape de l'historique
+    fn end_record(&mut self) {
+        if let (Some(history), Some(edits)) = (&mut self.history, self.grid.journal.take()) {
+            history.record(edits);
+        }
+    }
+
+    // Commencer à enregistrer les étapes de génération, à partir de l'état actuel de la grille
+    // (à appeler après `start_generation` pour enregistrer une génération complète)
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new(&self.grid));
+    }
+
+    // Arrêter l'enregistrement et le retourner
+    // None si aucun n'est en cours : `resize_grid`, `restore` et les modifications hors génération l'abandonnent
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    // Seules les étapes de génération sont enregistrées : après une autre modification de la grille
+    // (édition, remplissage, annulation, nouveau démarrage...), la relecture divergerait
+    fn abandon_recording(&mut self) {
+        self.recording = None;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+}
@@ -1,4 +1,40 @@
 use crate::Point;
+use crate::bitset::BitSet;
+use crate::dirty::{DirtyTile, DirtyTiles};
+use crate::error::MazeError;
+use crate::generators::{WallChange, WallOp, WallType};
+use crate::topology::Topology;
+use std::collections::BTreeMap;
+
+/// Orientation du tunnel qui passe sous une cellule de croisement (labyrinthes tissés)
+/// Le couloir du dessus traverse la cellule dans l'autre direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crossing {
+    /// Tunnel ouest-est sous un couloir nord-sud
+    Horizontal,
+    /// Tunnel nord-sud sous un couloir ouest-est
+    Vertical,
+}
+
+/// Modification élémentaire de la grille, enregistrée quand le journal est actif
+/// Chaque entrée peut être rejouée dans les deux sens : un mur par son opération, inversée
+/// pour l'annulation, un croisement par son ancienne et sa nouvelle valeur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GridEdit {
+    Wall(WallChange),
+    Crossing { index: usize, before: Option<Crossing>, after: Option<Crossing> },
+    /// Remplissage ou nettoyage de toute la grille, en une seule entrée plutôt qu'une par mur
+    Reset(Box<ResetEdit>),
+}
+
+/// État de la grille avant un remplissage ou un nettoyage, pour pouvoir l'annuler
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResetEdit {
+    present: bool,
+    vertical_walls: BitSet,
+    horizontal_walls: BitSet,
+    crossings: BTreeMap<usize, Crossing>,
+}
 
 /// Structure contenant les données de grille du labyrinthe
 /// Sépare les données de la logique de génération
@@ -6,39 +42,312 @@ use crate::Point;
 /// Convention des murs :
 /// - vertical_walls[i] : mur vertical à droite de la cellule i
 /// - horizontal_walls[i] : mur horizontal en bas de la cellule i
-/// - Les bordures du labyrinthe sont toujours considérées comme des murs
+/// - Les murs sont stockés un bit par cellule, pour les très grandes grilles
+/// - Les bordures du labyrinthe sont toujours considérées comme des murs, sauf en mode
+///   torique/cylindrique : les murs de la dernière colonne (ou ligne) séparent alors
+///   les bords opposés, qui deviennent voisins
 /// - Une cellule (x,y) a l'index : y * width + x
+/// - crossings[i] : tunnel passant sous la cellule i ; ses murs sont alors tous ouverts,
+///   mais elle n'est reliée qu'aux deux voisins de son couloir. Les croisements sont rares :
+///   seules les cellules concernées sont stockées
+///
+/// Le stockage reste privé : les générateurs externes passent par `has_wall`,
+/// `set_wall` et `carve_passage`, qui vérifient les coordonnées
 pub struct MazeGrid {
-    pub(crate) vertical_walls: Vec<bool>,
-    pub(crate) horizontal_walls: Vec<bool>,
+    pub(crate) vertical_walls: BitSet,
+    pub(crate) horizontal_walls: BitSet,
     pub width: usize,
     pub height: usize,
+    // Raccordement des bords gauche/droite et haut/bas
+    pub(crate) wrap_horizontal: bool,
+    pub(crate) wrap_vertical: bool,
+    pub(crate) crossings: BTreeMap<usize, Crossing>,
+    // Journal des modifications, actif uniquement pendant une opération enregistrée
+    pub(crate) journal: Option<Vec<GridEdit>>,
+    // Tuiles modifiées depuis le dernier rendu, si le suivi est actif
+    pub(crate) dirty: Option<DirtyTiles>,
 }
 
 impl MazeGrid {
     pub fn new(width: usize, height: usize) -> Self {
         let total_cells = width * height;
         Self {
-            vertical_walls: vec![false; total_cells],
-            horizontal_walls: vec![false; total_cells],
+            vertical_walls: BitSet::new(total_cells),
+            horizontal_walls: BitSet::new(total_cells),
             width,
             height,
+            wrap_horizontal: false,
+            wrap_vertical: false,
+            crossings: BTreeMap::new(),
+            journal: None,
+            dirty: None,
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Raccorde les bords gauche/droite (`horizontal`) et/ou haut/bas (`vertical`)
+    /// Les deux ensemble donnent un tore, un seul un cylindre
+    pub fn set_wrap(&mut self, horizontal: bool, vertical: bool) {
+        self.wrap_horizontal = horizontal;
+        self.wrap_vertical = vertical;
+        // Les bordures raccordées ne sont plus dessinées
+        if let Some(dirty) = &mut self.dirty {
+            dirty.mark_all();
+        }
+    }
+
+    /// Vrai si les bords gauche et droit sont raccordés
+    /// Il faut au moins 3 colonnes, sinon deux cellules seraient voisines deux fois
+    pub fn wraps_horizontally(&self) -> bool {
+        self.wrap_horizontal && self.width > 2
+    }
+
+    /// Vrai si les bords haut et bas sont raccordés (au moins 3 lignes)
+    pub fn wraps_vertically(&self) -> bool {
+        self.wrap_vertical && self.height > 2
+    }
+
     pub fn is_valid_point(&self, x: usize, y: usize) -> bool {
         x < self.width && y < self.height
     }
 
+    /// Comme `is_valid_point`, sous forme d'erreur pour l'API publique
+    pub fn check_point(&self, x: usize, y: usize) -> Result<(), MazeError> {
+        if self.is_valid_point(x, y) { Ok(()) } else { Err(MazeError::OutOfBounds { x, y }) }
+    }
+
     pub fn get_index(&self, x: usize, y: usize) -> usize {
         y * self.width + x
     }
 
+    /// Vérifie la présence d'un mur : vertical = à droite de (x, y), horizontal = en bas de (x, y)
+    /// Hors de la grille, retourne false
+    /// Sur la bordure, retourne la valeur stockée, qui n'a de sens qu'en mode torique
+    pub fn has_wall(&self, x: usize, y: usize, wall_type: WallType) -> bool {
+        if !self.is_valid_point(x, y) {
+            return false;
+        }
+
+        self.walls(wall_type).get(self.get_index(x, y))
+    }
+
+    fn walls(&self, wall_type: WallType) -> &BitSet {
+        match wall_type {
+            WallType::Vertical => &self.vertical_walls,
+            WallType::Horizontal => &self.horizontal_walls,
+        }
+    }
+
+    fn walls_mut(&mut self, wall_type: WallType) -> &mut BitSet {
+        match wall_type {
+            WallType::Vertical => &mut self.vertical_walls,
+            WallType::Horizontal => &mut self.horizontal_walls,
+        }
+    }
+
+    /// Vrai si le mur est présent ou s'il s'agit d'une bordure non raccordée
+    /// C'est ce qu'il faut dessiner
+    pub fn is_closed(&self, x: usize, y: usize, wall_type: WallType) -> bool {
+        self.is_fixed_border(x, y, wall_type) || self.has_wall(x, y, wall_type)
+    }
+
+    /// Vrai pour un mur de bordure qui ne sépare aucune cellule (ou hors de la grille)
+    fn is_fixed_border(&self, x: usize, y: usize, wall_type: WallType) -> bool {
+        match wall_type {
+            WallType::Vertical => x >= self.width.saturating_sub(1) && !self.wraps_horizontally(),
+            WallType::Horizontal => y >= self.height.saturating_sub(1) && !self.wraps_vertically(),
+        }
+    }
+
+    /// Place ou retire un mur intérieur et retourne le changement effectué
+    /// Retourne None si le mur est hors de la grille, sur la bordure, ou déjà dans l'état demandé
+    /// En mode torique, les murs de raccord de la dernière colonne/ligne sont modifiables
+    pub fn set_wall(&mut self, x: usize, y: usize, wall_type: WallType, present: bool) -> Option<WallChange> {
+        if !self.is_valid_point(x, y) || self.is_fixed_border(x, y, wall_type) || self.has_wall(x, y, wall_type) == present {
+            return None;
+        }
+
+        let index = self.get_index(x, y);
+        self.write_wall(index, wall_type, present);
+
+        Some(WallChange { x, y, wall_type, op: WallOp::from_present(present) })
+    }
+
+    /// Applique un changement reçu (historique, relecture, synchronisation réseau)
+    /// Contrairement à `set_wall`, les murs de bordure sont acceptés
+    /// Retourne false si la position est hors de la grille ou si le mur était déjà dans cet état
+    pub fn apply_change(&mut self, change: WallChange) -> bool {
+        if !self.is_valid_point(change.x, change.y) {
+            return false;
+        }
+
+        let present = change.op.is_added();
+        if self.has_wall(change.x, change.y, change.wall_type) == present {
+            return false;
+        }
+
+        let index = self.get_index(change.x, change.y);
+        self.write_wall(index, change.wall_type, present);
+        true
+    }
+
+    /// Changements qui transforment cette grille en `other`, dans l'ordre des cellules
+    /// Retourne None si les dimensions diffèrent ; les croisements ne sont pas comparés
+    pub fn diff(&self, other: &MazeGrid) -> Option<Vec<WallChange>> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+
+        // Comparaison mot à mot : seuls les murs différents sont parcourus
+        let mut wall_changes = Vec::new();
+        for wall_type in [WallType::Vertical, WallType::Horizontal] {
+            let after = other.walls(wall_type);
+            for index in self.walls(wall_type).differences(after) {
+                let (x, y) = (index % self.width, index / self.width);
+                wall_changes.push(WallChange { x, y, wall_type, op: WallOp::from_present(after.get(index)) });
+            }
+        }
+        wall_changes.sort_by_key(|change| change.y * self.width + change.x);
+
+        Some(wall_changes)
+    }
+
+    /// Ouvre un passage entre deux cellules adjacentes et retourne le mur supprimé
+    /// Retourne None si les cellules ne sont pas adjacentes ou déjà reliées,
+    /// ou si l'une d'elles est un croisement dont le tunnel passe dans cette direction
+    pub fn carve_passage(&mut self, from: Point, to: Point) -> Option<WallChange> {
+        let (x, y, wall_type) = self.wall_between(from, to)?;
+        if self.crosses_under(from, to, wall_type) {
+            return None;
+        }
+        self.set_wall(x, y, wall_type, false)
+    }
+
+    /// Tunnel passant sous la cellule (x, y), le cas échéant
+    pub fn crossing(&self, x: usize, y: usize) -> Option<Crossing> {
+        if self.is_valid_point(x, y) { self.crossings.get(&self.get_index(x, y)).copied() } else { None }
+    }
+
+    /// Vrai si le déplacement entre deux cellules adjacentes emprunte le tunnel d'un croisement :
+    /// les deux cellules ne sont alors pas reliées, même si le mur est ouvert
+    fn crosses_under(&self, from: Point, to: Point, wall_type: WallType) -> bool {
+        let tunnel = match wall_type {
+            WallType::Vertical => Crossing::Horizontal,
+            WallType::Horizontal => Crossing::Vertical,
+        };
+        self.crossing(from.x, from.y) == Some(tunnel) || self.crossing(to.x, to.y) == Some(tunnel)
+    }
+
+    /// Cellule intermédiaire et orientation d'un tunnel entre deux cellules alignées à distance 2
+    /// Les tunnels ne passent pas par les bords raccordés
+    fn tunnel_between(&self, from: Point, to: Point) -> Option<(Point, Crossing)> {
+        if !self.is_valid_point(from.x, from.y) || !self.is_valid_point(to.x, to.y) {
+            return None;
+        }
+
+        if from.y == to.y && from.x.abs_diff(to.x) == 2 {
+            Some((Point { x: from.x.min(to.x) + 1, y: from.y }, Crossing::Horizontal))
+        } else if from.x == to.x && from.y.abs_diff(to.y) == 2 {
+            Some((Point { x: from.x, y: from.y.min(to.y) + 1 }, Crossing::Vertical))
+        } else {
+            None
+        }
+    }
+
+    /// Vrai si un tunnel relie les deux cellules
+    pub fn has_tunnel(&self, from: Point, to: Point) -> bool {
+        match self.tunnel_between(from, to) {
+            Some((middle, crossing)) => self.crossing(middle.x, middle.y) == Some(crossing),
+            None => false,
+        }
+    }
+
+    /// Vrai si un tunnel peut être creusé entre deux cellules : la cellule du milieu doit être
+    /// un couloir perpendiculaire (reliée de part et d'autre, fermée vers les deux extrémités)
+    pub fn can_tunnel(&self, from: Point, to: Point) -> bool {
+        let Some((middle, crossing)) = self.tunnel_between(from, to) else {
+            return false;
+        };
+        if self.crossing(middle.x, middle.y).is_some() {
+            return false;
+        }
+
+        let (side_a, side_b) = match crossing {
+            Crossing::Horizontal if middle.y > 0 && middle.y + 1 < self.height => {
+                (Point { x: middle.x, y: middle.y - 1 }, Point { x: middle.x, y: middle.y + 1 })
+            }
+            Crossing::Vertical if middle.x > 0 && middle.x + 1 < self.width => {
+                (Point { x: middle.x - 1, y: middle.y }, Point { x: middle.x + 1, y: middle.y })
+            }
+            _ => return false,
+        };
+
+        // Murs réellement fermés : un mur déjà ouvert par un autre tunnel compte pour un mur
+        // dans `has_wall_between`, mais ne peut pas servir deux fois
+        let closed = |a: Point, b: Point| self.wall_between(a, b).is_some_and(|(x, y, wall_type)| self.has_wall(x, y, wall_type));
+        closed(from, middle)
+            && closed(middle, to)
+            && !self.has_wall_between(middle, side_a)
+            && !self.has_wall_between(middle, side_b)
+    }
+
+    /// Creuse un tunnel sous la cellule du milieu et retourne les deux murs supprimés
+    /// Retourne une liste vide si le tunnel n'est pas possible (voir `can_tunnel`)
+    pub fn tunnel_under(&mut self, from: Point, to: Point) -> Vec<WallChange> {
+        if !self.can_tunnel(from, to) {
+            return Vec::new();
+        }
+
+        let (middle, crossing) = self.tunnel_between(from, to).unwrap();
+        let wall_changes = [self.wall_between(from, middle), self.wall_between(middle, to)]
+            .into_iter()
+            .flatten()
+            .filter_map(|(x, y, wall_type)| self.set_wall(x, y, wall_type, false))
+            .collect();
+
+        let index = self.get_index(middle.x, middle.y);
+        self.write_crossing(index, Some(crossing));
+        wall_changes
+    }
+
+    /// Mur séparant deux cellules adjacentes, en tenant compte du raccord des bords
+    fn wall_between(&self, from: Point, to: Point) -> Option<(usize, usize, WallType)> {
+        if !self.is_valid_point(from.x, from.y) || !self.is_valid_point(to.x, to.y) {
+            return None;
+        }
+
+        let last_column = self.width - 1;
+        let last_row = self.height - 1;
+
+        if from.x == to.x {
+            match from.y.abs_diff(to.y) {
+                1 => Some((from.x, from.y.min(to.y), WallType::Horizontal)),
+                d if d == last_row && self.wraps_vertically() => Some((from.x, last_row, WallType::Horizontal)),
+                _ => None,
+            }
+        } else if from.y == to.y {
+            match from.x.abs_diff(to.x) {
+                1 => Some((from.x.min(to.x), from.y, WallType::Vertical)),
+                d if d == last_column && self.wraps_horizontally() => Some((last_column, from.y, WallType::Vertical)),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
     /// Supprime un mur vertical à la position (x, y)
     pub fn remove_vertical_wall(&mut self, x: usize, y: usize) {
         if self.is_valid_point(x, y) {
             let index = self.get_index(x, y);
-            self.vertical_walls[index] = false;
+            self.write_wall(index, WallType::Vertical, false);
         }
     }
 
@@ -46,7 +355,7 @@ impl MazeGrid {
     pub fn remove_horizontal_wall(&mut self, x: usize, y: usize) {
         if self.is_valid_point(x, y) {
             let index = self.get_index(x, y);
-            self.horizontal_walls[index] = false;
+            self.write_wall(index, WallType::Horizontal, false);
         }
     }
 
@@ -54,7 +363,7 @@ impl MazeGrid {
     pub fn add_vertical_wall(&mut self, x: usize, y: usize) {
         if self.is_valid_point(x, y) {
             let index = self.get_index(x, y);
-            self.vertical_walls[index] = true;
+            self.write_wall(index, WallType::Vertical, true);
         }
     }
 
@@ -62,7 +371,7 @@ impl MazeGrid {
     pub fn add_horizontal_wall(&mut self, x: usize, y: usize) {
         if self.is_valid_point(x, y) {
             let index = self.get_index(x, y);
-            self.horizontal_walls[index] = true;
+            self.write_wall(index, WallType::Horizontal, true);
         }
     }
 
@@ -79,16 +388,136 @@ impl MazeGrid {
         }
     }
 
+    /// Vérifie s'il y a un mur entre deux cellules adjacentes
+    /// Deux cellules non adjacentes sont toujours considérées comme séparées
+    pub fn has_wall_between(&self, from: Point, to: Point) -> bool {
+        match self.wall_between(from, to) {
+            Some((x, y, wall_type)) => self.crosses_under(from, to, wall_type) || self.has_wall(x, y, wall_type),
+            None => true,
+        }
+    }
+
     /// Remplit toute la grille de murs
     pub fn fill_grid(&mut self) {
-        self.vertical_walls.fill(true);
-        self.horizontal_walls.fill(true);
+        self.reset_walls(true);
     }
 
     /// Supprime tous les murs de la grille
     pub fn clear_grid(&mut self) {
-        self.vertical_walls.fill(false);
-        self.horizontal_walls.fill(false);
+        self.reset_walls(false);
+    }
+
+    fn reset_walls(&mut self, present: bool) {
+        // Journal actif : l'état précédent est conservé en bloc, deux bits par cellule
+        let before = self.journal.is_some().then(|| ResetEdit {
+            present,
+            vertical_walls: self.vertical_walls.clone(),
+            horizontal_walls: self.horizontal_walls.clone(),
+            crossings: self.crossings.clone(),
+        });
+
+        self.vertical_walls.fill(present);
+        self.horizontal_walls.fill(present);
+        self.crossings.clear();
+        if let Some(dirty) = &mut self.dirty {
+            dirty.mark_all();
+        }
+
+        if let (Some(journal), Some(before)) = (&mut self.journal, before) {
+            let changed = before.vertical_walls != self.vertical_walls
+                || before.horizontal_walls != self.horizontal_walls
+                || !before.crossings.is_empty();
+            if changed {
+                journal.push(GridEdit::Reset(Box::new(before)));
+            }
+        }
+    }
+
+    /// Remplace tous les murs et croisements, et retourne les murs modifiés
+    fn restore_walls(&mut self, vertical_walls: BitSet, horizontal_walls: BitSet, crossings: BTreeMap<usize, Crossing>) -> Vec<WallChange> {
+        let mut wall_changes = Vec::new();
+        for (wall_type, current, target) in [
+            (WallType::Vertical, &self.vertical_walls, &vertical_walls),
+            (WallType::Horizontal, &self.horizontal_walls, &horizontal_walls),
+        ] {
+            wall_changes.extend(current.differences(target).map(|index| WallChange {
+                x: index % self.width,
+                y: index / self.width,
+                wall_type,
+                op: WallOp::from_present(target.get(index)),
+            }));
+        }
+
+        self.vertical_walls = vertical_walls;
+        self.horizontal_walls = horizontal_walls;
+        self.crossings = crossings;
+        if let Some(dirty) = &mut self.dirty {
+            dirty.mark_all();
+        }
+        wall_changes
+    }
+
+    /// Écrit un mur et l'enregistre dans le journal s'il change
+    fn write_wall(&mut self, index: usize, wall_type: WallType, present: bool) {
+        let walls = self.walls_mut(wall_type);
+        if walls.get(index) == present {
+            return;
+        }
+
+        walls.set(index, present);
+        let (x, y) = (index % self.width, index / self.width);
+        if let Some(dirty) = &mut self.dirty {
+            // Le mur borde aussi la cellule voisine, de l'autre côté du raccord en mode torique
+            dirty.mark_cell(x, y);
+            match wall_type {
+                WallType::Vertical => dirty.mark_cell((x + 1) % self.width, y),
+                WallType::Horizontal => dirty.mark_cell(x, (y + 1) % self.height),
+            }
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.push(GridEdit::Wall(WallChange { x, y, wall_type, op: WallOp::from_present(present) }));
+        }
+    }
+
+    fn write_crossing(&mut self, index: usize, crossing: Option<Crossing>) {
+        let before = match crossing {
+            Some(crossing) => self.crossings.insert(index, crossing),
+            None => self.crossings.remove(&index),
+        };
+        if before == crossing {
+            return;
+        }
+
+        if let Some(dirty) = &mut self.dirty {
+            dirty.mark_cell(index % self.width, index / self.width);
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.push(GridEdit::Crossing { index, before, after: crossing });
+        }
+    }
+
+    /// Rejoue une modification enregistrée, vers l'avant ou en arrière
+    /// Retourne les murs modifiés
+    pub(crate) fn apply_edit(&mut self, edit: &GridEdit, forward: bool) -> Vec<WallChange> {
+        match edit {
+            GridEdit::Wall(change) => {
+                let change = if forward { *change } else { change.inverse() };
+                if self.apply_change(change) { vec![change] } else { Vec::new() }
+            }
+            GridEdit::Crossing { index, before, after } => {
+                self.write_crossing(*index, if forward { *after } else { *before });
+                Vec::new()
+            }
+            GridEdit::Reset(reset) if forward => {
+                let mut filled = BitSet::new(self.vertical_walls.len());
+                filled.fill(reset.present);
+                self.restore_walls(filled.clone(), filled, BTreeMap::new())
+            }
+            GridEdit::Reset(reset) => {
+                let ResetEdit { vertical_walls, horizontal_walls, crossings, .. } = reset.as_ref().clone();
+                self.restore_walls(vertical_walls, horizontal_walls, crossings)
+            }
+        }
     }
 
     /// Redimensionne la grille en conservant les murs existants quand c'est possible
@@ -98,28 +527,439 @@ impl MazeGrid {
         }
 
         let new_total_cells = new_width * new_height;
-        let mut new_vertical_walls = vec![false; new_total_cells];
-        let mut new_horizontal_walls = vec![false; new_total_cells];
+        let mut new_vertical_walls = BitSet::new(new_total_cells);
+        let mut new_horizontal_walls = BitSet::new(new_total_cells);
 
-        // Copier les murs existants dans les limites communes
+        // Copier les murs existants dans les limites communes, ligne par ligne
         let min_width = self.width.min(new_width);
         let min_height = self.height.min(new_height);
 
         for y in 0..min_height {
-            for x in 0..min_width {
-                let old_index = self.get_index(x, y);
-                let new_index = y * new_width + x;
-
-                // Copier les murs
-                new_vertical_walls[new_index] = self.vertical_walls[old_index];
-                new_horizontal_walls[new_index] = self.horizontal_walls[old_index];
-            }
+            let old_index = self.get_index(0, y);
+            let new_index = y * new_width;
+            new_vertical_walls.copy_from(new_index, &self.vertical_walls, old_index, min_width);
+            new_horizontal_walls.copy_from(new_index, &self.horizontal_walls, old_index, min_width);
         }
 
+        let new_crossings = self
+            .crossings
+            .iter()
+            .map(|(&index, &crossing)| (index % self.width, index / self.width, crossing))
+            .filter(|&(x, y, _)| x < min_width && y < min_height)
+            .map(|(x, y, crossing)| (y * new_width + x, crossing))
+            .collect();
+
         // Mettre à jour la grille
         self.vertical_walls = new_vertical_walls;
         self.horizontal_walls = new_horizontal_walls;
+        self.crossings = new_crossings;
         self.width = new_width;
         self.height = new_height;
+
+        if let Some(dirty) = &mut self.dirty {
+            *dirty = DirtyTiles::new(new_width, new_height, dirty.tile_size());
+        }
+    }
+
+    /// Murs d'un type, un bit par cellule dans l'ordre des index (bit de poids faible en premier),
+    /// complétés par des zéros jusqu'à un multiple de 8 octets
+    /// Sans copie : la couche WASM expose ce tampon directement au rendu
+    /// L'ordre des bits est celui de l'export binaire sur une machine little-endian (dont WASM)
+    pub fn wall_bytes(&self, wall_type: WallType) -> &[u8] {
+        self.walls(wall_type).as_bytes()
+    }
+
+    /// Active le suivi des zones modifiées, par tuiles de `tile_size` cellules de côté
+    /// Toute la grille est marquée au départ
+    pub fn enable_dirty_tracking(&mut self, tile_size: usize) {
+        self.dirty = Some(DirtyTiles::new(self.width, self.height, tile_size));
+    }
+
+    pub fn disable_dirty_tracking(&mut self) {
+        self.dirty = None;
+    }
+
+    /// Tuiles modifiées depuis l'appel précédent (vide si le suivi est inactif)
+    pub fn take_dirty_tiles(&mut self) -> Vec<DirtyTile> {
+        match &mut self.dirty {
+            Some(dirty) => dirty.take(self.width, self.height),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl MazeGrid {
+    /// Cellules alignées à deux cases (extrémités possibles d'un tunnel)
+    fn cells_at_distance_two(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let (x, y) = (cell % self.width, cell / self.width);
+        [
+            (x + 2 < self.width).then(|| cell + 2),
+            (x >= 2).then(|| cell - 2),
+            (y + 2 < self.height).then(|| cell + 2 * self.width),
+            (y >= 2).then(|| cell - 2 * self.width),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl Topology for MazeGrid {
+    type Change = WallChange;
+
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let mut neighbors = Vec::with_capacity(4);
+
+        // Est
+        if x + 1 < self.width {
+            neighbors.push(cell + 1);
+        } else if self.wraps_horizontally() {
+            neighbors.push(cell + 1 - self.width);
+        }
+        // Ouest
+        if x > 0 {
+            neighbors.push(cell - 1);
+        } else if self.wraps_horizontally() {
+            neighbors.push(cell + self.width - 1);
+        }
+        // Sud
+        if y + 1 < self.height {
+            neighbors.push(cell + self.width);
+        } else if self.wraps_vertically() {
+            neighbors.push(x);
+        }
+        // Nord
+        if y > 0 {
+            neighbors.push(cell - self.width);
+        } else if self.wraps_vertically() {
+            neighbors.push(self.get_index(x, self.height - 1));
+        }
+
+        neighbors
+    }
+
+    fn is_linked(&self, from: usize, to: usize) -> bool {
+        let (from, to) = (self.cell_point(from), self.cell_point(to));
+        !self.has_wall_between(from, to) || self.has_tunnel(from, to)
+    }
+
+    fn link(&mut self, from: usize, to: usize) -> Option<WallChange> {
+        self.carve_passage(self.cell_point(from), self.cell_point(to))
+    }
+
+    fn fill_walls(&mut self) {
+        self.fill_grid();
+    }
+
+    fn clear_walls(&mut self) {
+        self.clear_grid();
+    }
+
+    fn tunnel_candidates(&self, cell: usize) -> Vec<usize> {
+        self.cells_at_distance_two(cell)
+            .filter(|&other| self.can_tunnel(self.cell_point(cell), self.cell_point(other)))
+            .collect()
+    }
+
+    fn tunnel(&mut self, from: usize, to: usize) -> Vec<WallChange> {
+        self.tunnel_under(self.cell_point(from), self.cell_point(to))
+    }
+
+    fn passages(&self, cell: usize) -> Vec<usize> {
+        let tunnels = self
+            .cells_at_distance_two(cell)
+            .filter(|&other| self.has_tunnel(self.cell_point(cell), self.cell_point(other)));
+        self.neighbors(cell)
+            .into_iter()
+            .filter(|&neighbor| self.is_linked(cell, neighbor))
+            .chain(tunnels)
+            .collect()
+    }
+
+    fn cell_point(&self, cell: usize) -> Point {
+        Point { x: cell % self.width, y: cell / self.width }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{Algorithm, AlgorithmKind, GenerationAlgorithm};
+    use crate::solver::solve;
+
+    // Passages (chaque paire une fois), et vrai s'ils forment un arbre couvrant
+    fn passage_pairs(grid: &MazeGrid) -> Vec<(usize, usize)> {
+        (0..grid.cell_count())
+            .flat_map(|cell| grid.passages(cell).into_iter().filter(move |&other| cell < other).map(move |other| (cell, other)))
+            .collect()
+    }
+
+    fn is_spanning_tree(grid: &MazeGrid) -> bool {
+        let pairs = passage_pairs(grid);
+        let mut parents: Vec<usize> = (0..grid.cell_count()).collect();
+        fn find(parents: &mut [usize], mut cell: usize) -> usize {
+            while parents[cell] != cell {
+                cell = parents[cell];
+            }
+            cell
+        }
+        pairs.len() + 1 == grid.cell_count()
+            && pairs.iter().all(|&(a, b)| {
+                let (a, b) = (find(&mut parents, a), find(&mut parents, b));
+                parents[a] = b;
+                a != b
+            })
+    }
+
+    #[test]
+    fn set_wall_rejects_points_outside_the_grid() {
+        let mut grid = MazeGrid::new(3, 3);
+        assert_eq!(grid.set_wall(usize::MAX, 0, WallType::Vertical, true), None);
+        assert_eq!(grid.set_wall(0, usize::MAX, WallType::Horizontal, true), None);
+        assert_eq!(grid.set_wall(3, 0, WallType::Horizontal, true), None);
+        assert!(grid.is_closed(usize::MAX, 0, WallType::Vertical));
+        assert!(!grid.has_wall(usize::MAX, usize::MAX, WallType::Vertical));
+    }
+
+    #[test]
+    fn set_wall_changes_only_inner_walls() {
+        let mut grid = MazeGrid::new(3, 2);
+
+        let change = grid.set_wall(1, 0, WallType::Vertical, true);
+        assert_eq!(change, Some(WallChange { x: 1, y: 0, wall_type: WallType::Vertical, op: WallOp::Added }));
+        assert!(grid.has_wall(1, 0, WallType::Vertical));
+
+        // Déjà dans l'état demandé
+        assert_eq!(grid.set_wall(1, 0, WallType::Vertical, true), None);
+
+        // Bordures droite et basse
+        assert_eq!(grid.set_wall(2, 0, WallType::Vertical, true), None);
+        assert_eq!(grid.set_wall(0, 1, WallType::Horizontal, true), None);
+        assert!(grid.is_closed(2, 0, WallType::Vertical));
+
+        let change = grid.set_wall(1, 0, WallType::Vertical, false);
+        assert_eq!(change.map(|change| change.op), Some(WallOp::Removed));
+        assert!(!grid.is_closed(1, 0, WallType::Vertical));
+    }
+
+    #[test]
+    fn wrapped_borders_open_on_the_wrapped_axis_only() {
+        let mut grid = MazeGrid::new(4, 3);
+        grid.set_wrap(true, false);
+        grid.fill_grid();
+        let index = |x: usize, y: usize| y * 4 + x;
+
+        // Bords gauche et droit voisins ; haut et bas non
+        assert!(grid.neighbors(index(0, 1)).contains(&index(3, 1)));
+        assert!(!grid.neighbors(index(1, 0)).contains(&index(1, 2)));
+
+        // Le raccord est le mur vertical de la dernière colonne
+        let change = grid.carve_passage(Point::new(0, 1), Point::new(3, 1));
+        assert_eq!(change, Some(WallChange { x: 3, y: 1, wall_type: WallType::Vertical, op: WallOp::Removed }));
+        assert!(!grid.is_closed(3, 1, WallType::Vertical));
+        assert!(grid.is_linked(index(3, 1), index(0, 1)));
+
+        // Axe non raccordé : la bordure reste fermée
+        assert_eq!(grid.carve_passage(Point::new(1, 0), Point::new(1, 2)), None);
+        assert_eq!(grid.set_wall(1, 2, WallType::Horizontal, false), None);
+        assert!(grid.is_closed(1, 2, WallType::Horizontal));
+
+        // Raccord désactivé : le mur ouvert redevient une bordure fixe
+        grid.set_wrap(false, false);
+        assert!(grid.is_closed(3, 1, WallType::Vertical));
+        assert!(!grid.is_linked(index(3, 1), index(0, 1)));
+        assert!(!grid.neighbors(index(0, 1)).contains(&index(3, 1)));
+    }
+
+    #[test]
+    fn narrow_grids_do_not_wrap() {
+        // Avec deux colonnes, les cellules seraient voisines deux fois
+        let mut grid = MazeGrid::new(2, 5);
+        grid.set_wrap(true, true);
+        assert!(!grid.wraps_horizontally() && grid.wraps_vertically());
+        assert_eq!(grid.neighbors(0), vec![1, 2, 8]);
+    }
+
+    #[test]
+    fn generated_wrapped_mazes_are_spanning_trees() {
+        for (horizontal, vertical) in [(true, false), (false, true), (true, true)] {
+            for kind in AlgorithmKind::ALL {
+                let mut seam_used = false;
+                for seed in 0..10 {
+                    let mut grid = MazeGrid::new(5, 4);
+                    grid.set_wrap(horizontal, vertical);
+                    let mut generator = Algorithm::with_seed(kind, seed);
+                    generator.start(&mut grid);
+                    while !generator.is_finished() {
+                        generator.step(&mut grid);
+                    }
+
+                    assert!(is_spanning_tree(&grid), "{:?} ({}, {}) graine {}", kind, horizontal, vertical, seed);
+                    seam_used |= passage_pairs(&grid).iter().any(|&(a, b)| a.abs_diff(b) != 1 && a.abs_diff(b) != 5);
+                }
+
+                // La division récursive découpe des chambres rectangulaires et ferme les raccords
+                assert_eq!(seam_used, kind != AlgorithmKind::RecursiveDivision, "{:?} ({}, {})", kind, horizontal, vertical);
+            }
+        }
+    }
+
+    // Grille pleine de 3 x 3 avec un couloir passant par le centre
+    fn corridor(from: Point, to: Point) -> MazeGrid {
+        let mut grid = MazeGrid::new(3, 3);
+        grid.fill_grid();
+        grid.carve_passage(from, Point::new(1, 1));
+        grid.carve_passage(Point::new(1, 1), to);
+        grid
+    }
+
+    #[test]
+    fn tunnels_go_under_straight_corridors_only() {
+        let (west, east, north, south) = (Point::new(0, 1), Point::new(2, 1), Point::new(1, 0), Point::new(1, 2));
+
+        // Couloir nord-sud : tunnel ouest-est seulement
+        let grid = corridor(north, south);
+        assert!(grid.can_tunnel(west, east) && grid.can_tunnel(east, west));
+        assert!(!grid.can_tunnel(north, south));
+
+        // Couloir ouest-est : tunnel nord-sud seulement
+        let grid = corridor(west, east);
+        assert!(grid.can_tunnel(north, south));
+        assert!(!grid.can_tunnel(west, east));
+
+        // Virage : aucun tunnel
+        let grid = corridor(north, east);
+        assert!(!grid.can_tunnel(west, east) && !grid.can_tunnel(north, south));
+
+        // Cul-de-sac
+        let mut grid = MazeGrid::new(3, 3);
+        grid.fill_grid();
+        grid.carve_passage(north, Point::new(1, 1));
+        assert!(!grid.can_tunnel(west, east));
+
+        // Cellules non alignées, trop éloignées, ou tunnel sous la bordure
+        let grid = corridor(north, south);
+        assert!(!grid.can_tunnel(west, south));
+        assert!(!grid.can_tunnel(Point::new(0, 0), Point::new(2, 0)));
+        assert!(!grid.can_tunnel(Point::new(0, 1), Point::new(3, 1)));
+    }
+
+    #[test]
+    fn tunnel_is_recorded_as_a_crossing() {
+        let (west, east) = (Point::new(0, 1), Point::new(2, 1));
+        let mut grid = corridor(Point::new(1, 0), Point::new(1, 2));
+
+        let changes = grid.tunnel_under(west, east);
+        assert_eq!(
+            changes,
+            vec![
+                WallChange { x: 0, y: 1, wall_type: WallType::Vertical, op: WallOp::Removed },
+                WallChange { x: 1, y: 1, wall_type: WallType::Vertical, op: WallOp::Removed },
+            ]
+        );
+        assert_eq!(grid.crossing(1, 1), Some(Crossing::Horizontal));
+        assert_eq!(grid.crossing(0, 1), None);
+        assert!(grid.has_tunnel(west, east) && grid.has_tunnel(east, west));
+
+        // Les murs ouverts ne relient pas le centre aux extrémités du tunnel
+        let index = |point: Point| grid.get_index(point.x, point.y);
+        assert!(!grid.is_linked(index(west), 4) && !grid.is_linked(4, index(east)));
+        assert!(grid.is_linked(1, 4) && grid.is_linked(4, 7));
+        assert!(grid.has_wall_between(west, Point::new(1, 1)));
+        assert_eq!(grid.carve_passage(west, Point::new(1, 1)), None);
+
+        // Pas de second tunnel sous un croisement
+        assert!(!grid.can_tunnel(west, east));
+        assert!(grid.tunnel_under(west, east).is_empty());
+    }
+
+    #[test]
+    fn tunnels_do_not_reuse_the_walls_of_another_tunnel() {
+        // Deux couloirs ouest-est empilés au milieu d'une colonne
+        let mut grid = MazeGrid::new(3, 5);
+        grid.fill_grid();
+        for y in [1, 2] {
+            grid.carve_passage(Point::new(0, y), Point::new(1, y));
+            grid.carve_passage(Point::new(1, y), Point::new(2, y));
+        }
+        assert_eq!(grid.tunnel_under(Point::new(1, 0), Point::new(1, 2)).len(), 2);
+
+        // Le mur entre (1, 1) et (1, 2) est déjà ouvert par le premier tunnel
+        assert!(!grid.can_tunnel(Point::new(1, 1), Point::new(1, 3)));
+        assert!(grid.tunnel_under(Point::new(1, 1), Point::new(1, 3)).is_empty());
+        assert_eq!(grid.crossing(1, 2), None);
+    }
+
+    #[test]
+    fn solver_paths_through_tunnels() {
+        let (west, east, north, south) = (Point::new(0, 1), Point::new(2, 1), Point::new(1, 0), Point::new(1, 2));
+        let mut grid = corridor(north, south);
+        assert_eq!(solve(&grid, west, east), None);
+
+        grid.tunnel_under(west, east);
+        assert_eq!(solve(&grid, west, east), Some(vec![west, east]));
+        assert_eq!(solve(&grid, north, south), Some(vec![north, Point::new(1, 1), south]));
+
+        // Relier le tunnel au couloir par le coin nord-ouest : le chemin emprunte le tunnel puis le couloir
+        grid.carve_passage(Point::new(0, 0), west);
+        grid.carve_passage(Point::new(0, 0), north);
+        assert_eq!(solve(&grid, east, south), Some(vec![east, west, Point::new(0, 0), north, Point::new(1, 1), south]));
+    }
+
+    #[test]
+    fn diff_requires_same_dimensions() {
+        assert_eq!(MazeGrid::new(3, 4).diff(&MazeGrid::new(4, 3)), None);
+        assert_eq!(MazeGrid::new(3, 4).diff(&MazeGrid::new(3, 4)), Some(Vec::new()));
+    }
+
+    #[test]
+    fn diff_lists_changes_in_cell_order() {
+        let before = MazeGrid::new(3, 2);
+        let mut after = MazeGrid::new(3, 2);
+        after.add_horizontal_wall(2, 0);
+        after.add_vertical_wall(0, 1);
+        after.add_vertical_wall(1, 0);
+
+        let wall = |x, y, wall_type, op| WallChange { x, y, wall_type, op };
+        assert_eq!(
+            before.diff(&after),
+            Some(vec![
+                wall(1, 0, WallType::Vertical, WallOp::Added),
+                wall(2, 0, WallType::Horizontal, WallOp::Added),
+                wall(0, 1, WallType::Vertical, WallOp::Added),
+            ])
+        );
+        // Dans l'autre sens, les mêmes murs sont retirés
+        assert!(after.diff(&before).unwrap().iter().all(|change| change.op == WallOp::Removed));
+    }
+
+    #[test]
+    fn applying_a_diff_gives_the_other_grid() {
+        // Plus de 64 cellules : la comparaison porte sur plusieurs mots
+        let mut before = MazeGrid::new(20, 7);
+        let mut after = MazeGrid::new(20, 7);
+        for index in 0..140 {
+            let (x, y) = (index % 20, index / 20);
+            if index % 3 == 0 {
+                before.add_vertical_wall(x, y);
+            }
+            if index % 5 == 0 {
+                after.add_vertical_wall(x, y);
+            }
+            if index % 7 == 0 {
+                after.add_horizontal_wall(x, y);
+            }
+        }
+
+        let changes = before.diff(&after).unwrap();
+        assert!(changes.windows(2).all(|pair| pair[0].y * 20 + pair[0].x <= pair[1].y * 20 + pair[1].x));
+        for change in changes {
+            assert!(before.apply_change(change));
+        }
+        assert_eq!(before.diff(&after), Some(Vec::new()));
     }
 }
@@ -0,0 +1,366 @@
+//! Enregistrement d'une génération et relecture sans relancer l'algorithme
+//!
+//! Un `Recording` contient l'état initial de la grille puis, pour chaque étape,
+//! le résultat, les murs modifiés et les layers d'animation. `ReplayPlayer` le
+//! rejoue à n'importe quelle vitesse, en avant comme en arrière.
+
+use crate::Point;
+use crate::codec::{Reader, write_varint};
+use crate::export::{self, DecodeError};
+use crate::generators::{GenerationResult, WallChange, WallOp, WallType};
+use crate::maze_grid::MazeGrid;
+
+/// Signature en tête de chaque enregistrement binaire
+const MAGIC: &[u8; 4] = b"MREP";
+const VERSION: u8 = 1;
+
+/// Une étape de génération enregistrée
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub result: GenerationResult,
+    /// Murs modifiés par l'étape
+    pub changes: Vec<WallChange>,
+    /// Layers d'animation après l'étape
+    pub layers: Vec<Vec<Point>>,
+}
+
+/// Enregistrement complet d'une génération
+///
+/// Les croisements des labyrinthes tissés et le raccord des bords ne sont pas enregistrés :
+/// seuls les murs le sont
+#[derive(Debug, Clone)]
+pub struct Recording {
+    // État initial, au format binaire de `export::to_bytes`
+    initial: Vec<u8>,
+    frames: Vec<Frame>,
+}
+
+impl Recording {
+    /// Commence un enregistrement à partir de l'état actuel de la grille
+    pub fn new(grid: &MazeGrid) -> Self {
+        Self { initial: export::to_bytes(grid), frames: Vec::new() }
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Grille telle qu'elle était au début de l'enregistrement
+    pub fn initial_grid(&self) -> MazeGrid {
+        // Validé à la création ou au décodage
+        export::from_bytes(&self.initial).expect("état initial valide")
+    }
+
+    /// Format binaire compact :
+    /// - "MREP" + version (1 octet)
+    /// - état initial : longueur (u32 little-endian) puis grille au format `export::to_bytes`
+    /// - nombre d'étapes, puis pour chaque étape :
+    ///   - résultat (1 octet : 0 = en cours, 1 = terminé)
+    ///   - nombre de murs, puis x, y et un octet de drapeaux (bit 0 : horizontal, bit 1 : mur ajouté)
+    ///   - nombre de layers, puis pour chacun : longueur du préfixe commun avec le même layer
+    ///     de l'étape précédente, nombre de points suivants et leurs x, y
+    ///     (les piles et chemins ne changent qu'en fin de liste)
+    ///
+    /// Les entiers autres que la longueur de l'état initial sont des varints LEB128
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.initial.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.initial);
+
+        write_varint(&mut bytes, self.frames.len());
+        let mut previous_layers: &[Vec<Point>] = &[];
+        for frame in &self.frames {
+            bytes.push(match frame.result {
+                GenerationResult::Continue => 0,
+                GenerationResult::Finished => 1,
+            });
+
+            write_varint(&mut bytes, frame.changes.len());
+            for change in &frame.changes {
+                write_varint(&mut bytes, change.x);
+                write_varint(&mut bytes, change.y);
+                let horizontal = matches!(change.wall_type, WallType::Horizontal);
+                bytes.push(u8::from(horizontal) | (u8::from(change.op.is_added()) << 1));
+            }
+
+            write_varint(&mut bytes, frame.layers.len());
+            for (i, layer) in frame.layers.iter().enumerate() {
+                let previous = previous_layers.get(i).map_or(&[][..], Vec::as_slice);
+                let shared = layer.iter().zip(previous).take_while(|(a, b)| a == b).count();
+                write_varint(&mut bytes, shared);
+                write_varint(&mut bytes, layer.len() - shared);
+                for point in &layer[shared..] {
+                    write_varint(&mut bytes, point.x);
+                    write_varint(&mut bytes, point.y);
+                }
+            }
+            previous_layers = &frame.layers;
+        }
+
+        bytes
+    }
+
+    /// Relit un enregistrement écrit par `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() < 9 || &bytes[0..4] != MAGIC {
+            return Err(DecodeError::InvalidHeader);
+        }
+        if bytes[4] != VERSION {
+            return Err(DecodeError::UnsupportedVersion(bytes[4]));
+        }
+
+        let initial_len = u32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]) as usize;
+        let mut reader = Reader::new(&bytes[9..]);
+        let initial = reader.take(initial_len)?.to_vec();
+        let grid = export::from_bytes(&initial)?;
+
+        let frame_count = reader.varint()?;
+        let mut frames: Vec<Frame> = Vec::new();
+        for _ in 0..frame_count {
+            let result = match reader.byte()? {
+                0 => GenerationResult::Continue,
+                1 => GenerationResult::Finished,
+                _ => return Err(DecodeError::InvalidState),
+            };
+
+            let change_count = reader.varint()?;
+            let mut changes = Vec::new();
+            for _ in 0..change_count {
+                let (x, y) = (reader.varint()?, reader.varint()?);
+                if !grid.is_valid_point(x, y) {
+                    return Err(DecodeError::InvalidState);
+                }
+                let flags = reader.byte()?;
+                if flags > 3 {
+                    return Err(DecodeError::InvalidState);
+                }
+                let wall_type = if flags & 1 != 0 { WallType::Horizontal } else { WallType::Vertical };
+                let op = WallOp::from_present(flags & 2 != 0);
+                changes.push(WallChange { x, y, wall_type, op });
+            }
+
+            let layer_count = reader.varint()?;
+            let mut layers = Vec::new();
+            for i in 0..layer_count {
+                let previous = frames.last().and_then(|frame| frame.layers.get(i)).map_or(&[][..], Vec::as_slice);
+                let shared = reader.varint()?;
+                let mut layer = previous.get(..shared).ok_or(DecodeError::Truncated)?.to_vec();
+                let point_count = reader.varint()?;
+                for _ in 0..point_count {
+                    layer.push(Point { x: reader.varint()?, y: reader.varint()? });
+                }
+                layers.push(layer);
+            }
+
+            frames.push(Frame { result, changes, layers });
+        }
+
+        Ok(Self { initial, frames })
+    }
+}
+
+/// Relecture d'un enregistrement sur sa propre grille
+pub struct ReplayPlayer {
+    recording: Recording,
+    grid: MazeGrid,
+    // Nombre d'étapes appliquées
+    position: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(recording: Recording) -> Self {
+        let grid = recording.initial_grid();
+        Self { recording, grid, position: 0 }
+    }
+
+    pub fn grid(&self) -> &MazeGrid {
+        &self.grid
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Vrai quand toutes les étapes ont été appliquées
+    pub fn is_finished(&self) -> bool {
+        self.position == self.recording.len()
+    }
+
+    /// Applique jusqu'à `steps` étapes et retourne les murs modifiés
+    pub fn advance(&mut self, steps: usize) -> Vec<WallChange> {
+        let target = self.position.saturating_add(steps).min(self.recording.len());
+        let mut wall_changes = Vec::new();
+
+        for frame in &self.recording.frames[self.position..target] {
+            for change in &frame.changes {
+                if self.grid.apply_change(*change) {
+                    wall_changes.push(*change);
+                }
+            }
+        }
+
+        self.position = target;
+        wall_changes
+    }
+
+    /// Se place après `position` étapes
+    /// Revenir en arrière repart de l'état initial : le résultat est toujours exact
+    pub fn seek(&mut self, position: usize) -> Vec<WallChange> {
+        let position = position.min(self.recording.len());
+        if position >= self.position {
+            return self.advance(position - self.position);
+        }
+
+        // Murs qui diffèrent entre l'état courant et l'état cible
+        let previous = std::mem::replace(&mut self.grid, self.recording.initial_grid());
+        self.position = 0;
+        self.advance(position);
+
+        // Même grille initiale : les dimensions sont identiques
+        previous.diff(&self.grid).unwrap_or_default()
+    }
+
+    /// Layers d'animation de la dernière étape appliquée
+    pub fn cell_layers(&self) -> &[Vec<Point>] {
+        match self.position.checked_sub(1) {
+            Some(last) => &self.recording.frames[last].layers,
+            None => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::AlgorithmKind;
+    use crate::maze_generator::MazeGenerator;
+
+    // Génération complète enregistrée depuis son démarrage
+    fn record(kind: AlgorithmKind) -> (Recording, MazeGenerator) {
+        let mut generator = MazeGenerator::new(6, 4).unwrap();
+        generator.start_generation_with_seed(kind, 7);
+        generator.start_recording();
+        while !generator.is_finished() {
+            generator.generation_step().unwrap();
+        }
+        (generator.stop_recording().unwrap(), generator)
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        for kind in AlgorithmKind::ALL {
+            let (recording, generator) = record(kind);
+            let decoded = Recording::from_bytes(&recording.to_bytes()).unwrap();
+            assert_eq!(decoded.frames(), recording.frames());
+            assert_eq!(decoded.frames().last().map(|frame| frame.result), Some(GenerationResult::Finished));
+
+            // La relecture aboutit au labyrinthe généré
+            let mut player = ReplayPlayer::new(decoded);
+            player.advance(usize::MAX);
+            assert!(player.is_finished());
+            assert_eq!(player.grid().diff(generator.grid()), Some(Vec::new()));
+        }
+    }
+
+    #[test]
+    fn advance_stops_at_the_end() {
+        let (recording, generator) = record(AlgorithmKind::Kruskal);
+        let mut player = ReplayPlayer::new(recording.clone());
+        player.advance(2);
+        assert_eq!(player.position(), 2);
+
+        // usize::MAX : jusqu'à la fin, sans dépassement
+        player.advance(usize::MAX);
+        assert_eq!(player.position(), recording.len());
+        assert!(player.advance(usize::MAX).is_empty());
+        assert_eq!(player.grid().diff(generator.grid()), Some(Vec::new()));
+    }
+
+    #[test]
+    fn seek_backwards_returns_the_exact_difference() {
+        let (recording, _) = record(AlgorithmKind::Prim);
+        let mut player = ReplayPlayer::new(recording.clone());
+        player.seek(recording.len());
+
+        let mut expected = ReplayPlayer::new(recording);
+        expected.seek(3);
+        let before = export::from_bytes(&export::to_bytes(player.grid())).unwrap();
+        let changes = player.seek(3);
+
+        assert_eq!(player.position(), 3);
+        assert_eq!(player.grid().diff(expected.grid()), Some(Vec::new()));
+        assert_eq!(before.diff(player.grid()), Some(changes));
+        assert_eq!(player.cell_layers(), expected.cell_layers());
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let bytes = record(AlgorithmKind::Wilson).0.to_bytes();
+        for len in 0..bytes.len() {
+            assert!(Recording::from_bytes(&bytes[..len]).is_err(), "préfixe de {} octets accepté", len);
+        }
+    }
+
+    #[test]
+    fn rejects_corrupt_input() {
+        let grid = MazeGrid::new(3, 2);
+        let mut recording = Recording::new(&grid);
+        recording.push(Frame { result: GenerationResult::Continue, changes: Vec::new(), layers: Vec::new() });
+        let bytes = recording.to_bytes();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(Recording::from_bytes(&bad_magic).err(), Some(DecodeError::InvalidHeader));
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 9;
+        assert_eq!(Recording::from_bytes(&bad_version).err(), Some(DecodeError::UnsupportedVersion(9)));
+
+        // Mur hors de la grille
+        let change = WallChange { x: 3, y: 0, wall_type: WallType::Vertical, op: WallOp::Added };
+        let mut recording = Recording::new(&grid);
+        recording.push(Frame { result: GenerationResult::Continue, changes: vec![change], layers: Vec::new() });
+        assert_eq!(Recording::from_bytes(&recording.to_bytes()).err(), Some(DecodeError::InvalidState));
+
+        // Résultat d'étape et drapeaux de mur inconnus
+        let result_offset = bytes.len() - 3;
+        assert_eq!(bytes[result_offset], 0);
+        for (offset, value) in [(result_offset, 2), (result_offset, 0xff)] {
+            let mut bad = bytes.clone();
+            bad[offset] = value;
+            assert_eq!(Recording::from_bytes(&bad).err(), Some(DecodeError::InvalidState));
+        }
+        let change = WallChange { x: 1, y: 0, wall_type: WallType::Vertical, op: WallOp::Added };
+        let mut recording = Recording::new(&grid);
+        recording.push(Frame { result: GenerationResult::Finished, changes: vec![change], layers: Vec::new() });
+        let mut bad_flags = recording.to_bytes();
+        let flags_offset = bad_flags.len() - 2;
+        assert_eq!(bad_flags[flags_offset], 2);
+        bad_flags[flags_offset] = 4;
+        assert_eq!(Recording::from_bytes(&bad_flags).err(), Some(DecodeError::InvalidState));
+
+        // Préfixe commun plus long que le layer de l'étape précédente
+        let mut bytes = Recording::new(&grid).to_bytes();
+        bytes.truncate(bytes.len() - 1);
+        bytes.extend([1, 0, 0, 1, 5, 0]);
+        assert_eq!(Recording::from_bytes(&bytes).err(), Some(DecodeError::Truncated));
+    }
+}
@@ -0,0 +1,95 @@
+//! Instantané d'une génération en cours : la grille et l'état complet de l'algorithme
+//!
+//! Une génération animée peut être sauvegardée à mi-parcours, même d'une session à l'autre,
+//! puis reprise exactement où elle s'était arrêtée : la suite aléatoire repart du même mot.
+
+use crate::codec::{Reader, write_varint};
+use crate::error::MazeError;
+use crate::export::{self, DecodeError};
+use crate::generators::Algorithm;
+use crate::maze_generator::MAX_DIMENSION;
+use crate::maze_grid::{Crossing, MazeGrid};
+
+/// Signature en tête de chaque instantané binaire
+const MAGIC: &[u8; 4] = b"MSNP";
+const VERSION: u8 = 1;
+
+/// Format binaire :
+/// - "MSNP" + version (1 octet)
+/// - grille : longueur (u32 little-endian) puis murs au format `export::to_bytes`
+/// - raccord des bords (1 octet : bit 0 horizontal, bit 1 vertical)
+/// - nombre de croisements, puis pour chacun l'index de la cellule et l'orientation du tunnel
+///   (1 octet : 0 = ouest-est, 1 = nord-sud)
+/// - 0 si aucune génération n'est en cours, sinon 1, le type de l'algorithme et son état
+///
+/// Les entiers autres que la longueur de la grille sont des varints LEB128
+pub(crate) fn encode(grid: &MazeGrid, generator: Option<&Algorithm>) -> Result<Vec<u8>, MazeError> {
+    let walls = export::to_bytes(grid);
+    let mut bytes = Vec::with_capacity(9 + walls.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&(walls.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&walls);
+
+    bytes.push(u8::from(grid.wrap_horizontal) | (u8::from(grid.wrap_vertical) << 1));
+    write_varint(&mut bytes, grid.crossings.len());
+    for (&cell, crossing) in &grid.crossings {
+        write_varint(&mut bytes, cell);
+        bytes.push(match crossing {
+            Crossing::Horizontal => 0,
+            Crossing::Vertical => 1,
+        });
+    }
+
+    match generator {
+        Some(generator) => {
+            bytes.push(1);
+            generator.save(&mut bytes)?;
+        }
+        None => bytes.push(0),
+    }
+    Ok(bytes)
+}
+
+/// Relit un instantané écrit par `encode`
+pub(crate) fn decode(bytes: &[u8]) -> Result<(MazeGrid, Option<Algorithm>), MazeError> {
+    if bytes.len() < 5 || &bytes[0..4] != MAGIC {
+        return Err(DecodeError::InvalidHeader.into());
+    }
+    if bytes[4] != VERSION {
+        return Err(DecodeError::UnsupportedVersion(bytes[4]).into());
+    }
+
+    let mut reader = Reader::new(&bytes[5..]);
+    let walls_len = reader.take(4)?;
+    let walls_len = u32::from_le_bytes([walls_len[0], walls_len[1], walls_len[2], walls_len[3]]) as usize;
+    let mut grid = export::from_bytes(reader.take(walls_len)?)?;
+    if grid.width > MAX_DIMENSION || grid.height > MAX_DIMENSION {
+        return Err(MazeError::InvalidDimensions { width: grid.width, height: grid.height });
+    }
+
+    let wrap = reader.byte()?;
+    if wrap > 3 {
+        return Err(DecodeError::InvalidState.into());
+    }
+    grid.wrap_horizontal = wrap & 1 != 0;
+    grid.wrap_vertical = wrap & 2 != 0;
+
+    let cell_count = grid.width * grid.height;
+    for _ in 0..reader.list_len()? {
+        let cell = reader.cell(cell_count)?;
+        let crossing = match reader.byte()? {
+            0 => Crossing::Horizontal,
+            1 => Crossing::Vertical,
+            _ => return Err(DecodeError::InvalidState.into()),
+        };
+        grid.crossings.insert(cell, crossing);
+    }
+
+    let generator = match reader.byte()? {
+        0 => None,
+        1 => Some(Algorithm::load(&mut reader, &grid)?),
+        _ => return Err(DecodeError::InvalidState.into()),
+    };
+    Ok((grid, generator))
+}
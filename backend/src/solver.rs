@@ -0,0 +1,61 @@
+use crate::Point;
+use crate::maze_grid::MazeGrid;
+use crate::topology::Topology;
+use std::collections::VecDeque;
+
+/// Cherche le plus court chemin entre deux cellules avec un parcours en largeur (BFS)
+/// Retourne la liste des cellules traversées, départ et arrivée inclus,
+/// ou None si l'arrivée n'est pas atteignable
+pub fn solve(grid: &MazeGrid, start: Point, end: Point) -> Option<Vec<Point>> {
+    if !grid.is_valid_point(start.x, start.y) || !grid.is_valid_point(end.x, end.y) {
+        return None;
+    }
+
+    let path = solve_cells(grid, grid.get_index(start.x, start.y), grid.get_index(end.x, end.y))?;
+    Some(path.into_iter().map(|cell| grid.cell_point(cell)).collect())
+}
+
+/// Version générique du BFS, sur les index de cellules de n'importe quelle topologie
+pub fn solve_cells<G: Topology>(grid: &G, start: usize, end: usize) -> Option<Vec<usize>> {
+    let total_cells = grid.cell_count();
+    if start >= total_cells || end >= total_cells {
+        return None;
+    }
+
+    // previous[i] = cellule depuis laquelle on a atteint i
+    let mut previous: Vec<Option<usize>> = vec![None; total_cells];
+    let mut visited = vec![false; total_cells];
+    let mut queue = VecDeque::new();
+
+    visited[start] = true;
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if current == end {
+            break;
+        }
+
+        for next in grid.passages(current) {
+            if !visited[next] {
+                visited[next] = true;
+                previous[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    if !visited[end] {
+        return None;
+    }
+
+    // Reconstruire le chemin depuis l'arrivée
+    let mut path = vec![end];
+    let mut cell = end;
+    while let Some(parent) = previous[cell] {
+        path.push(parent);
+        cell = parent;
+    }
+    path.reverse();
+
+    Some(path)
+}
@@ -0,0 +1,309 @@
+use super::Topology;
+use crate::Point;
+use crate::geometry::{MazeGeometry, Segment};
+
+/// Murs possédés par une cellule triangulaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeltaWall {
+    /// Côté droit, partagé avec la cellule (x+1, y)
+    Right,
+    /// Base d'un triangle pointant vers le haut, partagée avec la cellule (x, y+1)
+    Base,
+}
+
+/// Mur modifié dans une grille triangulaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeltaWallChange {
+    pub x: usize,
+    pub y: usize,
+    pub wall: DeltaWall,
+}
+
+/// Grille de triangles alternés ("delta maze")
+///
+/// Convention :
+/// - La cellule (x, y) pointe vers le haut si (x + y) est pair, vers le bas sinon
+/// - Voisins : gauche, droite, et en dessous (triangle haut) ou au-dessus (triangle bas)
+/// - right_walls[i] : côté droit de la cellule i
+/// - base_walls[i] : base de la cellule i (utilisé uniquement pour les triangles pointant vers le haut)
+/// - Les bordures sont toujours considérées comme des murs
+/// - Une cellule (x,y) a l'index : y * width + x
+pub struct DeltaGrid {
+    right_walls: Vec<bool>,
+    base_walls: Vec<bool>,
+    width: usize,
+    height: usize,
+}
+
+/// Hauteur d'un triangle équilatéral de côté 1
+const TRIANGLE_HEIGHT: f64 = 0.866_025_403_784_438_6;
+
+impl DeltaGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let total_cells = width * height;
+        Self {
+            right_walls: vec![false; total_cells],
+            base_walls: vec![false; total_cells],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Index de la cellule (x, y) ; None hors de la grille
+    pub fn get_index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    /// Vrai si la cellule (x, y) pointe vers le haut
+    pub fn points_up(&self, x: usize, y: usize) -> bool {
+        (x + y).is_multiple_of(2)
+    }
+
+    /// Vérifie la présence d'un mur possédé par la cellule (x, y) ; les bordures sont des murs
+    /// Une cellule hors de la grille n'a aucun mur
+    pub fn has_wall(&self, x: usize, y: usize, wall: DeltaWall) -> bool {
+        let Some(index) = self.get_index(x, y) else {
+            return false;
+        };
+        match wall {
+            DeltaWall::Right => x + 1 >= self.width || self.right_walls[index],
+            DeltaWall::Base => {
+                self.points_up(x, y) && (y + 1 >= self.height || self.base_walls[index])
+            }
+        }
+    }
+
+    /// Mur séparant deux cellules adjacentes, exprimé depuis sa cellule propriétaire
+    fn wall_between(&self, from: usize, to: usize) -> Option<(usize, DeltaWall)> {
+        if from >= self.cell_count() || to >= self.cell_count() {
+            return None;
+        }
+
+        let (fx, fy) = (from % self.width, from / self.width);
+        let (tx, ty) = (to % self.width, to / self.width);
+
+        if fy == ty && fx.abs_diff(tx) == 1 {
+            Some((from.min(to), DeltaWall::Right))
+        } else if fx == tx && fy.abs_diff(ty) == 1 {
+            // Le triangle du dessus doit pointer vers le haut pour partager sa base
+            let upper = from.min(to);
+            if self.points_up(fx, fy.min(ty)) {
+                Some((upper, DeltaWall::Base))
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Sommets de la cellule (x, y), y vers le bas
+    fn vertices(&self, x: usize, y: usize) -> [(f64, f64); 3] {
+        let left = x as f64 / 2.0;
+        let top = y as f64 * TRIANGLE_HEIGHT;
+        let bottom = top + TRIANGLE_HEIGHT;
+
+        if self.points_up(x, y) {
+            // Base gauche, sommet, base droite
+            [(left, bottom), (left + 0.5, top), (left + 1.0, bottom)]
+        } else {
+            // Haut gauche, pointe basse, haut droit
+            [(left, top), (left + 0.5, bottom), (left + 1.0, top)]
+        }
+    }
+}
+
+impl Topology for DeltaGrid {
+    type Change = DeltaWallChange;
+
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        if cell >= self.cell_count() {
+            return Vec::new();
+        }
+
+        let (x, y) = (cell % self.width, cell / self.width);
+        let mut neighbors = Vec::with_capacity(3);
+
+        // Gauche
+        if x > 0 {
+            neighbors.push(cell - 1);
+        }
+        // Droite
+        if x + 1 < self.width {
+            neighbors.push(cell + 1);
+        }
+        // Base (triangle haut) ou pointe (triangle bas)
+        if self.points_up(x, y) {
+            if y + 1 < self.height {
+                neighbors.push(cell + self.width);
+            }
+        } else if y > 0 {
+            neighbors.push(cell - self.width);
+        }
+
+        neighbors
+    }
+
+    fn is_linked(&self, from: usize, to: usize) -> bool {
+        match self.wall_between(from, to) {
+            Some((owner, DeltaWall::Right)) => !self.right_walls[owner],
+            Some((owner, DeltaWall::Base)) => !self.base_walls[owner],
+            None => false,
+        }
+    }
+
+    fn link(&mut self, from: usize, to: usize) -> Option<DeltaWallChange> {
+        let (owner, wall) = self.wall_between(from, to)?;
+        let walls = match wall {
+            DeltaWall::Right => &mut self.right_walls,
+            DeltaWall::Base => &mut self.base_walls,
+        };
+        if !walls[owner] {
+            return None;
+        }
+
+        walls[owner] = false;
+        Some(DeltaWallChange {
+            x: owner % self.width,
+            y: owner / self.width,
+            wall,
+        })
+    }
+
+    fn fill_walls(&mut self) {
+        self.right_walls.fill(true);
+        self.base_walls.fill(true);
+    }
+
+    fn clear_walls(&mut self) {
+        self.right_walls.fill(false);
+        self.base_walls.fill(false);
+    }
+
+    fn cell_point(&self, cell: usize) -> Point {
+        Point { x: cell % self.width, y: cell / self.width }
+    }
+}
+
+impl MazeGeometry for DeltaGrid {
+    fn size(&self) -> (f64, f64) {
+        ((self.width + 1) as f64 / 2.0, self.height as f64 * TRIANGLE_HEIGHT)
+    }
+
+    fn wall_segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let segment = |(x1, y1): (f64, f64), (x2, y2): (f64, f64)| Segment { x1, y1, x2, y2 };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let [a, b, c] = self.vertices(x, y);
+                let up = self.points_up(x, y);
+
+                // Côté gauche : seulement en bordure, sinon il appartient au voisin de gauche
+                if x == 0 {
+                    segments.push(segment(a, b));
+                }
+                // Côté droit
+                if self.has_wall(x, y, DeltaWall::Right) {
+                    segments.push(segment(b, c));
+                }
+                // Base (triangle haut) ou bord supérieur en bordure (triangle bas)
+                let closed = if up { self.has_wall(x, y, DeltaWall::Base) } else { y == 0 };
+                if closed {
+                    segments.push(segment(a, c));
+                }
+            }
+        }
+
+        segments
+    }
+
+    fn cell_center(&self, cell: usize) -> (f64, f64) {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let top = y as f64 * TRIANGLE_HEIGHT;
+        let offset = if self.points_up(x, y) { 2.0 } else { 1.0 } * TRIANGLE_HEIGHT / 3.0;
+        (x as f64 / 2.0 + 0.5, top + offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::check_topology;
+
+    #[test]
+    fn neighbors_and_links_are_consistent() {
+        for (width, height) in [(1, 1), (1, 4), (4, 1), (3, 3), (4, 5), (5, 4)] {
+            check_topology(&mut DeltaGrid::new(width, height));
+        }
+    }
+
+    #[test]
+    fn triangles_alternate_and_share_bases_only_downwards() {
+        let grid = DeltaGrid::new(3, 3);
+        let index = |x, y| grid.get_index(x, y).unwrap();
+        assert!(grid.points_up(0, 0) && !grid.points_up(1, 0) && grid.points_up(1, 1));
+
+        // Coins et bords : pas de voisin au-delà de la grille
+        assert_eq!(grid.neighbors(index(0, 0)), vec![index(1, 0), index(0, 1)]);
+        assert_eq!(grid.neighbors(index(1, 0)), vec![index(0, 0), index(2, 0)]);
+        assert_eq!(grid.neighbors(index(2, 2)), vec![index(1, 2)]);
+        assert_eq!(grid.neighbors(index(1, 1)), vec![index(0, 1), index(2, 1), index(1, 2)]);
+
+        // Un triangle bas posé sur la pointe d'un triangle haut ne le touche que par un sommet
+        assert!(!grid.neighbors(index(1, 0)).contains(&index(1, 1)));
+    }
+
+    #[test]
+    fn link_reports_the_owned_wall() {
+        let mut grid = DeltaGrid::new(3, 3);
+        grid.fill_walls();
+        let index = |x: usize, y: usize| y * 3 + x;
+
+        assert_eq!(grid.link(index(0, 1), index(0, 0)), Some(DeltaWallChange { x: 0, y: 0, wall: DeltaWall::Base }));
+        assert!(!grid.has_wall(0, 0, DeltaWall::Base));
+        assert_eq!(grid.link(index(2, 0), index(1, 0)), Some(DeltaWallChange { x: 1, y: 0, wall: DeltaWall::Right }));
+        assert!(!grid.has_wall(1, 0, DeltaWall::Right));
+
+        // Bordures droite et basse, et base d'un triangle bas
+        grid.clear_walls();
+        assert!(grid.has_wall(2, 0, DeltaWall::Right));
+        assert!(grid.has_wall(0, 2, DeltaWall::Base));
+        assert!(!grid.has_wall(1, 0, DeltaWall::Base));
+    }
+
+    #[test]
+    fn out_of_range_cells_have_no_walls_or_neighbors() {
+        let mut grid = DeltaGrid::new(3, 3);
+        grid.fill_walls();
+        assert_eq!(grid.get_index(3, 0), None);
+        assert_eq!(grid.get_index(0, 3), None);
+        assert_eq!(grid.get_index(2, 2), Some(8));
+
+        for (x, y) in [(3, 0), (0, 3), (usize::MAX, usize::MAX)] {
+            assert!(!grid.has_wall(x, y, DeltaWall::Right) && !grid.has_wall(x, y, DeltaWall::Base));
+        }
+        for cell in [9, 11, usize::MAX] {
+            assert!(grid.neighbors(cell).is_empty());
+            assert!(!grid.is_linked(cell - 3, cell));
+        }
+
+        // La cellule 9 serait sous le triangle haut (0, 2) : sa base reste une bordure
+        assert_eq!(grid.link(6, 9), None);
+        assert_eq!(grid.link(9, 6), None);
+        assert!(grid.has_wall(0, 2, DeltaWall::Base));
+        assert!(DeltaGrid::new(0, 0).neighbors(0).is_empty());
+    }
+}
@@ -0,0 +1,324 @@
+use super::Topology;
+use crate::Point;
+use crate::geometry::{MazeGeometry, Segment};
+
+/// Les six côtés d'un hexagone à sommet plat ("flat-top")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexSide {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexSide {
+    pub const ALL: [HexSide; 6] = [
+        HexSide::North,
+        HexSide::NorthEast,
+        HexSide::SouthEast,
+        HexSide::South,
+        HexSide::SouthWest,
+        HexSide::NorthWest,
+    ];
+
+    pub fn opposite(&self) -> HexSide {
+        match self {
+            HexSide::North => HexSide::South,
+            HexSide::NorthEast => HexSide::SouthWest,
+            HexSide::SouthEast => HexSide::NorthWest,
+            HexSide::South => HexSide::North,
+            HexSide::SouthWest => HexSide::NorthEast,
+            HexSide::NorthWest => HexSide::SouthEast,
+        }
+    }
+
+    /// Index du mur dans le stockage de la cellule, pour les côtés dont elle est propriétaire
+    fn owned_slot(&self) -> Option<usize> {
+        match self {
+            HexSide::NorthEast => Some(0),
+            HexSide::SouthEast => Some(1),
+            HexSide::South => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Sommets (indices 0..6, angle = 60° * i, y vers le bas) délimitant ce côté
+    fn corners(&self) -> (usize, usize) {
+        match self {
+            HexSide::SouthEast => (0, 1),
+            HexSide::South => (1, 2),
+            HexSide::SouthWest => (2, 3),
+            HexSide::NorthWest => (3, 4),
+            HexSide::North => (4, 5),
+            HexSide::NorthEast => (5, 0),
+        }
+    }
+}
+
+/// Mur modifié dans une grille hexagonale
+/// `side` est toujours un côté possédé par la cellule (NorthEast, SouthEast ou South)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexWallChange {
+    pub x: usize,
+    pub y: usize,
+    pub side: HexSide,
+}
+
+/// Grille d'hexagones à sommet plat en coordonnées "odd-q" :
+/// les colonnes impaires sont décalées d'une demi-cellule vers le bas
+///
+/// Convention des murs :
+/// - Chaque cellule stocke ses murs NorthEast, SouthEast et South
+/// - Les autres côtés appartiennent à la cellule voisine
+/// - Les bordures sont toujours considérées comme des murs
+/// - Une cellule (x,y) a l'index : y * width + x
+pub struct HexGrid {
+    walls: Vec<[bool; 3]>,
+    width: usize,
+    height: usize,
+}
+
+impl HexGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            walls: vec![[false; 3]; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Index de la cellule (x, y) ; None hors de la grille
+    pub fn get_index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    /// Cellule voisine dans une direction, si les deux cellules sont dans la grille
+    pub fn neighbor(&self, cell: usize, side: HexSide) -> Option<usize> {
+        if cell >= self.cell_count() {
+            return None;
+        }
+
+        let (x, y) = ((cell % self.width) as isize, (cell / self.width) as isize);
+        let odd = x % 2 == 1;
+
+        let (nx, ny) = match side {
+            HexSide::North => (x, y - 1),
+            HexSide::South => (x, y + 1),
+            HexSide::NorthEast => (x + 1, if odd { y } else { y - 1 }),
+            HexSide::SouthEast => (x + 1, if odd { y + 1 } else { y }),
+            HexSide::NorthWest => (x - 1, if odd { y } else { y - 1 }),
+            HexSide::SouthWest => (x - 1, if odd { y + 1 } else { y }),
+        };
+
+        if nx < 0 || ny < 0 {
+            None
+        } else {
+            self.get_index(nx as usize, ny as usize)
+        }
+    }
+
+    /// Vérifie la présence d'un mur sur un côté de la cellule (les bordures sont des murs)
+    /// Une cellule hors de la grille n'a aucun mur
+    pub fn has_wall(&self, cell: usize, side: HexSide) -> bool {
+        if cell >= self.cell_count() {
+            return false;
+        }
+
+        match self.neighbor(cell, side) {
+            None => true,
+            Some(neighbor) => {
+                let (owner, slot) = self.wall_slot(cell, neighbor, side);
+                self.walls[owner][slot]
+            }
+        }
+    }
+
+    /// Cellule propriétaire et emplacement du mur situé sur `side` de `cell`
+    fn wall_slot(&self, cell: usize, neighbor: usize, side: HexSide) -> (usize, usize) {
+        match side.owned_slot() {
+            Some(slot) => (cell, slot),
+            None => (neighbor, side.opposite().owned_slot().unwrap()),
+        }
+    }
+
+    fn side_towards(&self, from: usize, to: usize) -> Option<HexSide> {
+        HexSide::ALL.into_iter().find(|side| self.neighbor(from, *side) == Some(to))
+    }
+
+    /// Centre d'une cellule ; un hexagone fait 1 unité de large
+    fn center(&self, cell: usize) -> (f64, f64) {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let row_height = 3f64.sqrt() / 2.0;
+        let offset = if x % 2 == 1 { row_height / 2.0 } else { 0.0 };
+        (0.75 * x as f64 + 0.5, row_height * y as f64 + offset + row_height / 2.0)
+    }
+
+    fn corner(&self, cell: usize, corner: usize) -> (f64, f64) {
+        let (cx, cy) = self.center(cell);
+        let angle = std::f64::consts::PI / 3.0 * corner as f64;
+        (cx + 0.5 * angle.cos(), cy + 0.5 * angle.sin())
+    }
+}
+
+impl Topology for HexGrid {
+    type Change = HexWallChange;
+
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        HexSide::ALL.iter().filter_map(|side| self.neighbor(cell, *side)).collect()
+    }
+
+    fn is_linked(&self, from: usize, to: usize) -> bool {
+        match self.side_towards(from, to) {
+            Some(side) => !self.has_wall(from, side),
+            None => false,
+        }
+    }
+
+    fn link(&mut self, from: usize, to: usize) -> Option<HexWallChange> {
+        let side = self.side_towards(from, to)?;
+        let (owner, slot) = self.wall_slot(from, to, side);
+        if !self.walls[owner][slot] {
+            return None;
+        }
+
+        self.walls[owner][slot] = false;
+        let owned_side = if owner == from { side } else { side.opposite() };
+        Some(HexWallChange {
+            x: owner % self.width,
+            y: owner / self.width,
+            side: owned_side,
+        })
+    }
+
+    fn fill_walls(&mut self) {
+        self.walls.fill([true; 3]);
+    }
+
+    fn clear_walls(&mut self) {
+        self.walls.fill([false; 3]);
+    }
+
+    fn cell_point(&self, cell: usize) -> Point {
+        Point { x: cell % self.width, y: cell / self.width }
+    }
+}
+
+impl MazeGeometry for HexGrid {
+    fn size(&self) -> (f64, f64) {
+        let row_height = 3f64.sqrt() / 2.0;
+        let extra_row = if self.width > 1 { row_height / 2.0 } else { 0.0 };
+        (0.75 * self.width as f64 + 0.25, row_height * self.height as f64 + extra_row)
+    }
+
+    fn wall_segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+
+        for cell in 0..self.cell_count() {
+            for side in HexSide::ALL {
+                // Chaque mur intérieur n'est dessiné qu'une fois, par sa cellule propriétaire
+                let draw = match self.neighbor(cell, side) {
+                    None => true,
+                    Some(_) => side.owned_slot().is_some() && self.has_wall(cell, side),
+                };
+
+                if draw {
+                    let (start, end) = side.corners();
+                    let (x1, y1) = self.corner(cell, start);
+                    let (x2, y2) = self.corner(cell, end);
+                    segments.push(Segment { x1, y1, x2, y2 });
+                }
+            }
+        }
+
+        segments
+    }
+
+    fn cell_center(&self, cell: usize) -> (f64, f64) {
+        self.center(cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::check_topology;
+
+    #[test]
+    fn neighbors_and_links_are_consistent() {
+        for (width, height) in [(1, 1), (1, 4), (4, 1), (3, 3), (4, 5), (5, 4)] {
+            check_topology(&mut HexGrid::new(width, height));
+        }
+    }
+
+    #[test]
+    fn border_cells_have_fewer_neighbors() {
+        let grid = HexGrid::new(3, 3);
+        let index = |x, y| grid.get_index(x, y).unwrap();
+        let count = |x, y| grid.neighbors(index(x, y)).len();
+
+        // Coins : la colonne impaire est décalée vers le bas
+        assert_eq!(count(0, 0), 2);
+        assert_eq!(count(2, 0), 2);
+        assert_eq!(count(0, 2), 3);
+        assert_eq!(count(2, 2), 3);
+        // Bords et centre
+        assert_eq!(count(1, 0), 5);
+        assert_eq!(count(1, 2), 3);
+        assert_eq!(count(1, 1), 6);
+
+        assert_eq!(grid.neighbor(index(1, 1), HexSide::NorthWest), Some(index(0, 1)));
+        assert_eq!(grid.neighbor(index(1, 1), HexSide::SouthEast), Some(index(2, 2)));
+        assert_eq!(grid.neighbor(index(0, 0), HexSide::NorthEast), None);
+    }
+
+    #[test]
+    fn out_of_range_cells_have_no_walls_or_neighbors() {
+        let mut grid = HexGrid::new(3, 3);
+        grid.fill_walls();
+        assert_eq!(grid.get_index(3, 0), None);
+        assert_eq!(grid.get_index(0, 3), None);
+        assert_eq!(grid.get_index(2, 2), Some(8));
+
+        // La cellule 9 serait sous la grille : son voisin nord (6) existe, mais pas elle
+        for cell in [9, 10, usize::MAX] {
+            assert!(HexSide::ALL.iter().all(|&side| grid.neighbor(cell, side).is_none() && !grid.has_wall(cell, side)));
+            assert!(grid.neighbors(cell).is_empty());
+            assert!(!grid.is_linked(6, cell) && !grid.is_linked(cell, 6));
+            assert_eq!(grid.link(cell, 6), None);
+        }
+        assert!(HexGrid::new(0, 0).neighbor(0, HexSide::South).is_none());
+    }
+
+    #[test]
+    fn link_reports_the_owned_wall() {
+        let mut grid = HexGrid::new(3, 3);
+        grid.fill_walls();
+        let (center, north_west) = (grid.get_index(1, 1).unwrap(), grid.get_index(0, 1).unwrap());
+
+        // Le côté nord-ouest du centre est le côté sud-est de son voisin
+        let change = grid.link(center, north_west);
+        assert_eq!(change, Some(HexWallChange { x: 0, y: 1, side: HexSide::SouthEast }));
+        assert!(!grid.has_wall(center, HexSide::NorthWest));
+        assert!(!grid.has_wall(north_west, HexSide::SouthEast));
+        assert!(grid.has_wall(center, HexSide::North));
+
+        // Les bordures restent des murs, même sans aucun mur intérieur
+        grid.clear_walls();
+        assert!(grid.has_wall(0, HexSide::North));
+        assert!(grid.has_wall(8, HexSide::SouthEast));
+    }
+}
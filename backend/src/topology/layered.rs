@@ -0,0 +1,342 @@
+use super::Topology;
+use crate::Point;
+use crate::generators::{WallChange, WallType};
+use crate::geometry::{MazeGeometry, Segment, StairMarker, Stairs};
+use crate::maze_grid::MazeGrid;
+
+/// Murs d'une grille à plusieurs étages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayeredWall {
+    /// Mur vertical à droite de la cellule, sur son étage
+    Vertical,
+    /// Mur horizontal en bas de la cellule, sur son étage
+    Horizontal,
+    /// Plafond de la cellule : un escalier vers l'étage supérieur quand il est ouvert
+    Ceiling,
+}
+
+/// Mur modifié dans une grille à plusieurs étages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayeredWallChange {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+    pub wall: LayeredWall,
+}
+
+/// Labyrinthe en 3D : une pile de `depth` étages de `width` x `height` cellules
+///
+/// Convention :
+/// - Chaque étage est un `MazeGrid`, avec ses propres murs verticaux et horizontaux
+/// - ceilings[i] : plafond de la cellule i, qui la sépare de la cellule au-dessus (z + 1)
+/// - Le plafond du dernier étage est toujours fermé
+/// - Une cellule (x,y,z) a l'index : z * width * height + y * width + x
+pub struct LayeredGrid {
+    floors: Vec<MazeGrid>,
+    ceilings: Vec<bool>,
+    width: usize,
+    height: usize,
+}
+
+impl LayeredGrid {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            floors: (0..depth).map(|_| MazeGrid::new(width, height)).collect(),
+            ceilings: vec![false; width * height * depth],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Nombre d'étages
+    pub fn depth(&self) -> usize {
+        self.floors.len()
+    }
+
+    /// Index de la cellule (x, y, z) ; None hors de la grille
+    pub fn get_index(&self, x: usize, y: usize, z: usize) -> Option<usize> {
+        (x < self.width && y < self.height && z < self.depth()).then(|| (z * self.height + y) * self.width + x)
+    }
+
+    /// Murs d'un étage, utilisables directement par les exports (SVG, PDF...) ; None au-delà du dernier étage
+    pub fn floor(&self, z: usize) -> Option<&MazeGrid> {
+        self.floors.get(z)
+    }
+
+    /// Étage prêt à être exporté en SVG ou en PDF, escaliers compris ; None au-delà du dernier étage
+    pub fn floor_view(&self, z: usize) -> Option<FloorView<'_>> {
+        Some(FloorView { grid: self, floor: self.floor(z)?, z })
+    }
+
+    /// Vrai si un escalier relie la cellule (x, y, z) à celle du dessus ; faux hors de la grille
+    pub fn has_stairs_up(&self, x: usize, y: usize, z: usize) -> bool {
+        z + 1 < self.depth() && self.get_index(x, y, z).is_some_and(|cell| !self.ceilings[cell])
+    }
+
+    /// Vrai si un escalier relie la cellule (x, y, z) à celle du dessous
+    pub fn has_stairs_down(&self, x: usize, y: usize, z: usize) -> bool {
+        z > 0 && self.has_stairs_up(x, y, z - 1)
+    }
+
+    /// Cellules de l'étage `z` ayant un escalier vers le haut, pour le rendu
+    pub fn stairs_up(&self, z: usize) -> Vec<Point> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Point { x, y }))
+            .filter(|point| self.has_stairs_up(point.x, point.y, z))
+            .collect()
+    }
+
+    fn floor_size(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// (étage, index de la cellule sur son étage)
+    fn locate(&self, cell: usize) -> (usize, usize) {
+        (cell / self.floor_size(), cell % self.floor_size())
+    }
+}
+
+/// Un étage d'une grille 3D vu par les exports : les murs de son `MazeGrid`
+/// et un marqueur sur chaque cellule ayant un escalier
+pub struct FloorView<'a> {
+    grid: &'a LayeredGrid,
+    floor: &'a MazeGrid,
+    z: usize,
+}
+
+impl MazeGeometry for FloorView<'_> {
+    fn size(&self) -> (f64, f64) {
+        self.floor.size()
+    }
+
+    fn wall_segments(&self) -> Vec<Segment> {
+        self.floor.wall_segments()
+    }
+
+    fn cell_center(&self, cell: usize) -> (f64, f64) {
+        self.floor.cell_center(cell)
+    }
+
+    fn stair_markers(&self) -> Vec<StairMarker> {
+        let mut markers = Vec::new();
+        for y in 0..self.grid.height {
+            for x in 0..self.grid.width {
+                let stairs = match (self.grid.has_stairs_up(x, y, self.z), self.grid.has_stairs_down(x, y, self.z)) {
+                    (true, true) => Stairs::Both,
+                    (true, false) => Stairs::Up,
+                    (false, true) => Stairs::Down,
+                    (false, false) => continue,
+                };
+                let (cx, cy) = (x as f64 + 0.5, y as f64 + 0.5);
+                markers.push(StairMarker { cx, cy, stairs });
+            }
+        }
+        markers
+    }
+}
+
+impl Topology for LayeredGrid {
+    type Change = LayeredWallChange;
+
+    fn cell_count(&self) -> usize {
+        self.ceilings.len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        if cell >= self.cell_count() {
+            return Vec::new();
+        }
+
+        let (z, local) = self.locate(cell);
+        let offset = z * self.floor_size();
+        let mut neighbors: Vec<usize> = self.floors[z].neighbors(local).into_iter().map(|n| n + offset).collect();
+
+        // Haut
+        if z + 1 < self.depth() {
+            neighbors.push(cell + self.floor_size());
+        }
+        // Bas
+        if z > 0 {
+            neighbors.push(cell - self.floor_size());
+        }
+
+        neighbors
+    }
+
+    fn is_linked(&self, from: usize, to: usize) -> bool {
+        if from >= self.cell_count() || to >= self.cell_count() {
+            return false;
+        }
+
+        let (from_z, from_local) = self.locate(from);
+        let (to_z, to_local) = self.locate(to);
+
+        if from_z == to_z {
+            self.floors[from_z].is_linked(from_local, to_local)
+        } else if from_local == to_local && from_z.abs_diff(to_z) == 1 {
+            !self.ceilings[from.min(to)]
+        } else {
+            false
+        }
+    }
+
+    fn link(&mut self, from: usize, to: usize) -> Option<LayeredWallChange> {
+        if from >= self.cell_count() || to >= self.cell_count() {
+            return None;
+        }
+
+        let (from_z, from_local) = self.locate(from);
+        let (to_z, to_local) = self.locate(to);
+
+        if from_z == to_z {
+            let WallChange { x, y, wall_type, .. } = self.floors[from_z].link(from_local, to_local)?;
+            let wall = match wall_type {
+                WallType::Vertical => LayeredWall::Vertical,
+                WallType::Horizontal => LayeredWall::Horizontal,
+            };
+            Some(LayeredWallChange { x, y, z: from_z, wall })
+        } else if from_local == to_local && from_z.abs_diff(to_z) == 1 {
+            let below = from.min(to);
+            if !self.ceilings[below] {
+                return None;
+            }
+
+            self.ceilings[below] = false;
+            Some(LayeredWallChange {
+                x: from_local % self.width,
+                y: from_local / self.width,
+                z: from_z.min(to_z),
+                wall: LayeredWall::Ceiling,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn fill_walls(&mut self) {
+        self.floors.iter_mut().for_each(MazeGrid::fill_grid);
+        self.ceilings.fill(true);
+    }
+
+    fn clear_walls(&mut self) {
+        self.floors.iter_mut().for_each(MazeGrid::clear_grid);
+        self.ceilings.fill(false);
+    }
+
+    /// Les étages sont empilés verticalement : y = z * height + y
+    fn cell_point(&self, cell: usize) -> Point {
+        let (z, local) = self.locate(cell);
+        Point { x: local % self.width, y: z * self.height + local / self.width }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::check_topology;
+
+    #[test]
+    fn neighbors_and_links_are_consistent() {
+        for (width, height, depth) in [(1, 1, 1), (1, 1, 3), (3, 2, 1), (3, 3, 2), (2, 3, 4)] {
+            check_topology(&mut LayeredGrid::new(width, height, depth));
+        }
+    }
+
+    #[test]
+    fn stairs_connect_adjacent_floors_only() {
+        let grid = LayeredGrid::new(3, 3, 3);
+        let index = |x, y, z| grid.get_index(x, y, z).unwrap();
+        let count = |x, y, z| grid.neighbors(index(x, y, z)).len();
+
+        // Coin du premier et du dernier étage : deux voisins sur l'étage, un seul escalier
+        assert_eq!(count(0, 0, 0), 3);
+        assert_eq!(count(2, 2, 2), 3);
+        // Centre de l'étage intermédiaire : quatre voisins, deux escaliers
+        assert_eq!(count(1, 1, 1), 6);
+        assert!(!grid.neighbors(index(1, 1, 0)).contains(&index(1, 1, 2)));
+        assert!(!grid.neighbors(index(1, 1, 0)).contains(&index(1, 2, 1)));
+    }
+
+    #[test]
+    fn link_opens_stairs_and_floor_walls() {
+        let mut grid = LayeredGrid::new(3, 2, 2);
+        grid.fill_walls();
+        let index = |x: usize, y: usize, z: usize| (z * 2 + y) * 3 + x;
+
+        // Escalier : plafond de la cellule du dessous
+        let change = grid.link(index(2, 1, 1), index(2, 1, 0));
+        assert_eq!(change, Some(LayeredWallChange { x: 2, y: 1, z: 0, wall: LayeredWall::Ceiling }));
+        assert!(grid.has_stairs_up(2, 1, 0) && grid.has_stairs_down(2, 1, 1));
+        assert!(!grid.has_stairs_up(2, 1, 1), "le dernier étage n'a pas de plafond ouvrable");
+        assert_eq!(grid.stairs_up(0), vec![Point { x: 2, y: 1 }]);
+
+        // Mur d'un étage : visible sur le `MazeGrid` de cet étage seulement
+        let change = grid.link(index(0, 0, 1), index(1, 0, 1));
+        assert_eq!(change, Some(LayeredWallChange { x: 0, y: 0, z: 1, wall: LayeredWall::Vertical }));
+        assert!(!grid.floor(1).unwrap().has_wall(0, 0, WallType::Vertical));
+        assert!(grid.floor(0).unwrap().has_wall(0, 0, WallType::Vertical));
+
+        // Les étages sont empilés pour l'affichage
+        assert_eq!(grid.cell_point(index(1, 1, 1)), Point { x: 1, y: 3 });
+    }
+
+    #[test]
+    fn out_of_range_cells_have_no_stairs_or_neighbors() {
+        let mut grid = LayeredGrid::new(3, 2, 2);
+        grid.clear_walls();
+        assert_eq!(grid.get_index(3, 0, 0), None);
+        assert_eq!(grid.get_index(0, 2, 0), None);
+        assert_eq!(grid.get_index(0, 0, 2), None);
+        assert_eq!(grid.get_index(2, 1, 1), Some(11));
+        assert!(grid.floor(2).is_none());
+
+        // (3, 0, 0) aurait l'index de (0, 1, 0), dont le plafond est ouvert
+        assert!(grid.has_stairs_up(0, 1, 0));
+        assert!(!grid.has_stairs_up(3, 0, 0) && !grid.has_stairs_down(3, 0, 1));
+        assert!(!grid.has_stairs_up(0, 2, 0) && !grid.has_stairs_down(usize::MAX, 0, 1));
+
+        grid.fill_walls();
+        for cell in [12, 13, usize::MAX] {
+            assert!(grid.neighbors(cell).is_empty());
+            assert!(!grid.is_linked(cell - 6, cell));
+            assert_eq!(grid.link(cell - 6, cell), None);
+        }
+        assert!(LayeredGrid::new(0, 0, 2).neighbors(0).is_empty());
+    }
+
+    #[test]
+    fn floor_views_mark_stairs_in_both_directions() {
+        let mut grid = LayeredGrid::new(2, 2, 3);
+        grid.fill_walls();
+        let index = |x: usize, y: usize, z: usize| (z * 2 + y) * 2 + x;
+        grid.link(index(1, 0, 0), index(1, 0, 1));
+        grid.link(index(1, 0, 1), index(1, 0, 2));
+        grid.link(index(0, 1, 1), index(0, 1, 2));
+
+        let markers = |z| grid.floor_view(z).unwrap().stair_markers();
+        assert_eq!(markers(0), vec![StairMarker { cx: 1.5, cy: 0.5, stairs: Stairs::Up }]);
+        assert_eq!(
+            markers(1),
+            vec![
+                StairMarker { cx: 1.5, cy: 0.5, stairs: Stairs::Both },
+                StairMarker { cx: 0.5, cy: 1.5, stairs: Stairs::Up },
+            ]
+        );
+        assert_eq!(markers(2).len(), 2);
+        assert!(markers(2).iter().all(|marker| marker.stairs == Stairs::Down));
+        assert!(grid.floor_view(3).is_none());
+
+        // Les murs sont ceux de l'étage
+        let view = grid.floor_view(1).unwrap();
+        assert_eq!(view.wall_segments(), grid.floor(1).unwrap().wall_segments());
+        assert_eq!(view.size(), (2.0, 2.0));
+    }
+}
@@ -0,0 +1,122 @@
+pub mod delta;
+pub mod hex;
+pub mod layered;
+pub mod polar;
+pub mod upsilon;
+
+pub use delta::{DeltaGrid, DeltaWall, DeltaWallChange};
+pub use hex::{HexGrid, HexSide, HexWallChange};
+pub use layered::{FloorView, LayeredGrid, LayeredWall, LayeredWallChange};
+pub use polar::{PolarGrid, PolarWall, PolarWallChange};
+pub use upsilon::{UpsilonGrid, UpsilonSide, UpsilonWallChange};
+
+use crate::Point;
+
+/// Abstraction de la forme de la grille, partagée par les générateurs et le solveur
+///
+/// Les cellules sont identifiées par un index `0..cell_count()`.
+/// Les générateurs n'ont besoin que du graphe des cellules voisines et de la
+/// possibilité d'ouvrir un passage : ils fonctionnent donc sur toute topologie
+/// (carrée, hexagonale, triangulaire, circulaire, octogones et carrés, sur plusieurs étages...) sans connaître la géométrie des murs
+pub trait Topology {
+    /// Description d'un mur modifié, retournée par les générateurs (ex: `WallChange` pour la grille carrée)
+    type Change: Clone + std::fmt::Debug;
+
+    /// Nombre total de cellules
+    fn cell_count(&self) -> usize;
+
+    /// Cellules adjacentes, qu'un mur les sépare ou non
+    fn neighbors(&self, cell: usize) -> Vec<usize>;
+
+    /// Vrai si un passage relie deux cellules adjacentes
+    fn is_linked(&self, from: usize, to: usize) -> bool;
+
+    /// Ouvre le passage entre deux cellules adjacentes
+    /// Retourne None si les cellules ne sont pas adjacentes ou déjà reliées
+    fn link(&mut self, from: usize, to: usize) -> Option<Self::Change>;
+
+    /// Place tous les murs
+    fn fill_walls(&mut self);
+
+    /// Retire tous les murs
+    fn clear_walls(&mut self);
+
+    /// Coordonnées d'affichage d'une cellule (pour les layers d'animation)
+    fn cell_point(&self, cell: usize) -> Point;
+
+    /// Cellules que l'on peut atteindre depuis `cell` en creusant un tunnel sous une cellule
+    /// voisine (labyrinthes tissés) ; aucune par défaut
+    fn tunnel_candidates(&self, _cell: usize) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Creuse un tunnel vers une cellule retournée par `tunnel_candidates`
+    /// Retourne les murs supprimés, ou une liste vide si le tunnel n'est pas possible
+    fn tunnel(&mut self, _from: usize, _to: usize) -> Vec<Self::Change> {
+        Vec::new()
+    }
+
+    /// Cellules reliées à `cell` par un passage, tunnels compris (utilisé par le solveur)
+    fn passages(&self, cell: usize) -> Vec<usize> {
+        self.neighbors(cell).into_iter().filter(|&neighbor| self.is_linked(cell, neighbor)).collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::Topology;
+
+    // Paires de cellules reliées par un passage (chaque paire une fois)
+    fn linked_pairs<G: Topology>(grid: &G) -> usize {
+        (0..grid.cell_count())
+            .map(|cell| grid.neighbors(cell).into_iter().filter(|&n| cell < n && grid.is_linked(cell, n)).count())
+            .sum()
+    }
+
+    /// Invariants communs à toutes les topologies : voisinage symétrique et sans doublon,
+    /// `link` / `is_linked` cohérents dans les deux sens, et aucun passage entre cellules non adjacentes
+    pub(crate) fn check_topology<G: Topology>(grid: &mut G) {
+        let cell_count = grid.cell_count();
+        for cell in 0..cell_count {
+            let neighbors = grid.neighbors(cell);
+            for (i, &neighbor) in neighbors.iter().enumerate() {
+                assert!(neighbor < cell_count && neighbor != cell, "voisin invalide {} -> {}", cell, neighbor);
+                assert!(!neighbors[..i].contains(&neighbor), "voisin en double {} -> {}", cell, neighbor);
+                assert!(grid.neighbors(neighbor).contains(&cell), "voisinage non symétrique {} -> {}", cell, neighbor);
+            }
+        }
+
+        // Ouvrir les passages un par un, alternativement depuis l'une ou l'autre cellule
+        grid.fill_walls();
+        assert_eq!(linked_pairs(grid), 0);
+        let mut opened = 0;
+        for cell in 0..cell_count {
+            for neighbor in grid.neighbors(cell).into_iter().filter(|&n| cell < n) {
+                let (from, to) = if opened % 2 == 0 { (cell, neighbor) } else { (neighbor, cell) };
+                assert!(grid.link(from, to).is_some(), "passage {} -> {} non ouvert", from, to);
+                assert!(grid.is_linked(from, to) && grid.is_linked(to, from));
+                assert!(grid.link(to, from).is_none(), "passage {} -> {} ouvert deux fois", to, from);
+                opened += 1;
+                assert_eq!(linked_pairs(grid), opened, "l'ouverture de {} -> {} a modifié un autre mur", from, to);
+            }
+        }
+
+        for cell in 0..cell_count {
+            let mut passages = grid.passages(cell);
+            let mut neighbors = grid.neighbors(cell);
+            passages.sort_unstable();
+            neighbors.sort_unstable();
+            assert_eq!(passages, neighbors);
+
+            for other in (0..cell_count).filter(|other| *other != cell && !neighbors.contains(other)) {
+                assert!(!grid.is_linked(cell, other), "cellules non adjacentes {} et {} reliées", cell, other);
+                assert!(grid.link(cell, other).is_none());
+            }
+        }
+
+        grid.fill_walls();
+        assert_eq!(linked_pairs(grid), 0);
+        grid.clear_walls();
+        assert_eq!(linked_pairs(grid), opened);
+    }
+}
@@ -0,0 +1,367 @@
+use super::Topology;
+use crate::Point;
+use crate::geometry::{CircularArc, MazeGeometry, Segment};
+use std::f64::consts::TAU;
+
+/// Murs possédés par une cellule polaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PolarWall {
+    /// Arc intérieur, partagé avec la cellule de l'anneau précédent
+    Inward,
+    /// Rayon du côté horaire, partagé avec la cellule suivante du même anneau
+    Clockwise,
+}
+
+/// Mur modifié dans une grille polaire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolarWallChange {
+    pub ring: usize,
+    pub index: usize,
+    pub wall: PolarWall,
+}
+
+/// Grille circulaire ("theta maze") faite d'anneaux concentriques d'épaisseur 1
+///
+/// Convention :
+/// - L'anneau 0 est une cellule unique au centre
+/// - Chaque anneau contient un multiple du nombre de cellules de l'anneau précédent,
+///   choisi pour garder des cellules à peu près carrées : une cellule peut donc avoir
+///   plusieurs voisins vers l'extérieur
+/// - Chaque cellule stocke son mur intérieur et son mur horaire
+/// - Le cercle extérieur est toujours un mur
+/// - La cellule `index` de l'anneau `ring` a l'index : offsets[ring] + index
+pub struct PolarGrid {
+    inward_walls: Vec<bool>,
+    clockwise_walls: Vec<bool>,
+    ring_sizes: Vec<usize>,
+    offsets: Vec<usize>,
+}
+
+impl PolarGrid {
+    pub fn new(rings: usize) -> Self {
+        let mut ring_sizes = Vec::with_capacity(rings);
+        for ring in 0..rings {
+            let size = match ring_sizes.last() {
+                None => 1,
+                Some(&previous) => {
+                    // Largeur qu'aurait une cellule sans subdivision, comparée à l'épaisseur de l'anneau
+                    let estimated_width = TAU * ring as f64 / previous as f64;
+                    previous * (estimated_width.round() as usize).max(1)
+                }
+            };
+            ring_sizes.push(size);
+        }
+
+        let offsets: Vec<usize> = ring_sizes
+            .iter()
+            .scan(0, |total, &size| {
+                let offset = *total;
+                *total += size;
+                Some(offset)
+            })
+            .collect();
+        let total_cells = ring_sizes.iter().sum();
+
+        Self {
+            inward_walls: vec![false; total_cells],
+            clockwise_walls: vec![false; total_cells],
+            ring_sizes,
+            offsets,
+        }
+    }
+
+    /// Nombre d'anneaux, cellule centrale comprise
+    pub fn rings(&self) -> usize {
+        self.ring_sizes.len()
+    }
+
+    /// Nombre de cellules de l'anneau ; 0 au-delà du dernier anneau
+    pub fn ring_size(&self, ring: usize) -> usize {
+        self.ring_sizes.get(ring).copied().unwrap_or(0)
+    }
+
+    /// Index de la cellule ; None hors de la grille
+    pub fn get_index(&self, ring: usize, index: usize) -> Option<usize> {
+        (index < self.ring_size(ring)).then(|| self.cell_index(ring, index))
+    }
+
+    /// Index d'une cellule dont la position a déjà été validée
+    fn cell_index(&self, ring: usize, index: usize) -> usize {
+        self.offsets[ring] + index
+    }
+
+    /// Vérifie la présence d'un mur possédé par une cellule
+    /// La cellule centrale et les cellules hors de la grille n'en possèdent aucun
+    pub fn has_wall(&self, ring: usize, index: usize, wall: PolarWall) -> bool {
+        let Some(cell) = self.get_index(ring, index) else {
+            return false;
+        };
+        ring > 0
+            && match wall {
+                PolarWall::Inward => self.inward_walls[cell],
+                PolarWall::Clockwise => self.clockwise_walls[cell],
+            }
+    }
+
+    /// (anneau, position dans l'anneau) d'une cellule
+    fn locate(&self, cell: usize) -> (usize, usize) {
+        let ring = self.offsets.partition_point(|&offset| offset <= cell) - 1;
+        (ring, cell - self.offsets[ring])
+    }
+
+    /// Cellule de l'anneau précédent touchant le mur intérieur
+    fn parent(&self, ring: usize, index: usize) -> usize {
+        let ratio = self.ring_sizes[ring] / self.ring_sizes[ring - 1];
+        self.cell_index(ring - 1, index / ratio)
+    }
+
+    /// Mur séparant deux cellules adjacentes, exprimé depuis sa cellule propriétaire
+    fn wall_between(&self, from: usize, to: usize) -> Option<(usize, PolarWall)> {
+        if from >= self.cell_count() || to >= self.cell_count() {
+            return None;
+        }
+
+        let (from_ring, from_index) = self.locate(from);
+        let (to_ring, to_index) = self.locate(to);
+
+        if from_ring == to_ring && from_ring > 0 {
+            let size = self.ring_sizes[from_ring];
+            if (from_index + 1) % size == to_index {
+                Some((from, PolarWall::Clockwise))
+            } else if (to_index + 1) % size == from_index {
+                Some((to, PolarWall::Clockwise))
+            } else {
+                None
+            }
+        } else if to_ring == from_ring + 1 && self.parent(to_ring, to_index) == from {
+            Some((to, PolarWall::Inward))
+        } else if from_ring == to_ring + 1 && self.parent(from_ring, from_index) == to {
+            Some((from, PolarWall::Inward))
+        } else {
+            None
+        }
+    }
+
+    /// Angle du bord antihoraire de la cellule `index` d'un anneau de `size` cellules
+    fn angle(index: usize, size: usize) -> f64 {
+        TAU * index as f64 / size as f64
+    }
+
+    /// Centre du cercle, en unités de cellule
+    fn origin(&self) -> f64 {
+        self.rings() as f64
+    }
+
+    fn polar_point(&self, radius: f64, angle: f64) -> (f64, f64) {
+        (self.origin() + radius * angle.cos(), self.origin() + radius * angle.sin())
+    }
+
+    fn arc(&self, radius: f64, start_angle: f64, end_angle: f64) -> CircularArc {
+        CircularArc { cx: self.origin(), cy: self.origin(), radius, start_angle, end_angle }
+    }
+}
+
+impl Topology for PolarGrid {
+    type Change = PolarWallChange;
+
+    fn cell_count(&self) -> usize {
+        self.inward_walls.len()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        if cell >= self.cell_count() {
+            return Vec::new();
+        }
+
+        let (ring, index) = self.locate(cell);
+        let size = self.ring_sizes[ring];
+        let mut neighbors = Vec::with_capacity(5);
+
+        if ring > 0 {
+            // Horaire, antihoraire, intérieur
+            neighbors.push(self.cell_index(ring, (index + 1) % size));
+            neighbors.push(self.cell_index(ring, (index + size - 1) % size));
+            neighbors.push(self.parent(ring, index));
+        }
+        // Extérieur : une ou plusieurs cellules de l'anneau suivant
+        if ring + 1 < self.rings() {
+            let ratio = self.ring_sizes[ring + 1] / size;
+            let first = self.cell_index(ring + 1, index * ratio);
+            neighbors.extend(first..first + ratio);
+        }
+
+        neighbors
+    }
+
+    fn is_linked(&self, from: usize, to: usize) -> bool {
+        match self.wall_between(from, to) {
+            Some((owner, PolarWall::Inward)) => !self.inward_walls[owner],
+            Some((owner, PolarWall::Clockwise)) => !self.clockwise_walls[owner],
+            None => false,
+        }
+    }
+
+    fn link(&mut self, from: usize, to: usize) -> Option<PolarWallChange> {
+        let (owner, wall) = self.wall_between(from, to)?;
+        let walls = match wall {
+            PolarWall::Inward => &mut self.inward_walls,
+            PolarWall::Clockwise => &mut self.clockwise_walls,
+        };
+        if !walls[owner] {
+            return None;
+        }
+
+        walls[owner] = false;
+        let (ring, index) = self.locate(owner);
+        Some(PolarWallChange { ring, index, wall })
+    }
+
+    fn fill_walls(&mut self) {
+        self.inward_walls.fill(true);
+        self.clockwise_walls.fill(true);
+    }
+
+    fn clear_walls(&mut self) {
+        self.inward_walls.fill(false);
+        self.clockwise_walls.fill(false);
+    }
+
+    /// x = position dans l'anneau, y = anneau
+    fn cell_point(&self, cell: usize) -> Point {
+        let (ring, index) = self.locate(cell);
+        Point { x: index, y: ring }
+    }
+}
+
+impl MazeGeometry for PolarGrid {
+    fn size(&self) -> (f64, f64) {
+        (2.0 * self.origin(), 2.0 * self.origin())
+    }
+
+    /// Murs radiaux entre deux cellules d'un même anneau
+    fn wall_segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+
+        for ring in 1..self.rings() {
+            let size = self.ring_sizes[ring];
+            for index in 0..size {
+                if self.has_wall(ring, index, PolarWall::Clockwise) {
+                    let angle = Self::angle(index + 1, size);
+                    let (x1, y1) = self.polar_point(ring as f64, angle);
+                    let (x2, y2) = self.polar_point((ring + 1) as f64, angle);
+                    segments.push(Segment { x1, y1, x2, y2 });
+                }
+            }
+        }
+
+        segments
+    }
+
+    /// Murs intérieurs des cellules et cercle extérieur
+    fn wall_arcs(&self) -> Vec<CircularArc> {
+        let mut arcs = Vec::new();
+
+        for ring in 1..self.rings() {
+            let size = self.ring_sizes[ring];
+            for index in 0..size {
+                if self.has_wall(ring, index, PolarWall::Inward) {
+                    arcs.push(self.arc(ring as f64, Self::angle(index, size), Self::angle(index + 1, size)));
+                }
+            }
+        }
+
+        // Bordure : un arc par cellule du dernier anneau, ou deux demi-cercles
+        // quand seule la cellule centrale existe (un arc SVG ne peut pas boucler sur lui-même)
+        if let Some(&size) = self.ring_sizes.last() {
+            let radius = self.origin();
+            let pieces = size.max(2);
+            for index in 0..pieces {
+                arcs.push(self.arc(radius, Self::angle(index, pieces), Self::angle(index + 1, pieces)));
+            }
+        }
+
+        arcs
+    }
+
+    fn cell_center(&self, cell: usize) -> (f64, f64) {
+        let (ring, index) = self.locate(cell);
+        if ring == 0 {
+            return (self.origin(), self.origin());
+        }
+
+        let size = self.ring_sizes[ring];
+        self.polar_point(ring as f64 + 0.5, (Self::angle(index, size) + Self::angle(index + 1, size)) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::check_topology;
+
+    #[test]
+    fn neighbors_and_links_are_consistent() {
+        for rings in 0..8 {
+            check_topology(&mut PolarGrid::new(rings));
+        }
+    }
+
+    #[test]
+    fn rings_subdivide_to_keep_cells_square() {
+        let grid = PolarGrid::new(7);
+        let index = |ring, position| grid.get_index(ring, position).unwrap();
+        let sizes: Vec<usize> = (0..grid.rings()).map(|ring| grid.ring_size(ring)).collect();
+        assert_eq!(sizes, vec![1, 6, 12, 24, 24, 24, 48]);
+        assert_eq!(grid.cell_count(), sizes.iter().sum::<usize>());
+
+        // Anneau subdivisé : deux voisins vers l'extérieur ; sinon un seul
+        assert_eq!(grid.neighbors(index(2, 5)).len(), 3 + 2);
+        assert_eq!(grid.neighbors(index(3, 5)).len(), 3 + 1);
+        // Dernier anneau : aucun voisin vers l'extérieur
+        assert_eq!(grid.neighbors(index(6, 0)).len(), 3);
+        // L'anneau se referme : la dernière cellule touche la première
+        assert!(grid.neighbors(index(1, 5)).contains(&index(1, 0)));
+    }
+
+    #[test]
+    fn centre_cell_touches_the_whole_first_ring() {
+        let mut grid = PolarGrid::new(3);
+        let centre = grid.get_index(0, 0).unwrap();
+        let first_ring: Vec<usize> = (0..6).map(|index| grid.get_index(1, index).unwrap()).collect();
+        assert_eq!(grid.neighbors(centre), first_ring);
+
+        // Le centre ne possède aucun mur : le passage est le mur intérieur de la cellule de l'anneau 1
+        grid.fill_walls();
+        assert!(!grid.has_wall(0, 0, PolarWall::Inward));
+        assert_eq!(grid.link(centre, first_ring[2]), Some(PolarWallChange { ring: 1, index: 2, wall: PolarWall::Inward }));
+        assert!(grid.is_linked(first_ring[2], centre));
+        assert!(!grid.is_linked(first_ring[3], centre));
+
+        // Le mur horaire de la dernière cellule sépare de la première
+        let change = grid.link(first_ring[0], first_ring[5]);
+        assert_eq!(change, Some(PolarWallChange { ring: 1, index: 5, wall: PolarWall::Clockwise }));
+        assert!(!grid.has_wall(1, 5, PolarWall::Clockwise));
+    }
+
+    #[test]
+    fn out_of_range_cells_have_no_walls_or_neighbors() {
+        let mut grid = PolarGrid::new(3);
+        grid.fill_walls();
+        assert_eq!(grid.ring_size(3), 0);
+        assert_eq!(grid.get_index(1, 6), None);
+        assert_eq!(grid.get_index(3, 0), None);
+        assert_eq!(grid.get_index(2, 11), Some(grid.cell_count() - 1));
+
+        for (ring, index) in [(1, 6), (3, 0), (usize::MAX, usize::MAX)] {
+            assert!(!grid.has_wall(ring, index, PolarWall::Inward));
+            assert!(!grid.has_wall(ring, index, PolarWall::Clockwise));
+        }
+        let outside = grid.cell_count();
+        for cell in [outside, outside + 1, usize::MAX] {
+            assert!(grid.neighbors(cell).is_empty());
+            assert!(!grid.is_linked(outside - 1, cell));
+            assert_eq!(grid.link(outside - 1, cell), None);
+        }
+        assert!(PolarGrid::new(0).neighbors(0).is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+use super::Topology;
+use crate::Point;
+use crate::geometry::{MazeGeometry, Segment};
+
+/// Les huit côtés possibles d'une cellule ; les carrés n'utilisent que les quatre orthogonaux
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpsilonSide {
+    East,
+    West,
+    South,
+    North,
+    SouthEast,
+    SouthWest,
+    NorthEast,
+    NorthWest,
+}
+
+impl UpsilonSide {
+    pub const ALL: [UpsilonSide; 8] = [
+        UpsilonSide::East,
+        UpsilonSide::West,
+        UpsilonSide::South,
+        UpsilonSide::North,
+        UpsilonSide::SouthEast,
+        UpsilonSide::SouthWest,
+        UpsilonSide::NorthEast,
+        UpsilonSide::NorthWest,
+    ];
+
+    pub fn opposite(&self) -> UpsilonSide {
+        match self {
+            UpsilonSide::East => UpsilonSide::West,
+            UpsilonSide::West => UpsilonSide::East,
+            UpsilonSide::South => UpsilonSide::North,
+            UpsilonSide::North => UpsilonSide::South,
+            UpsilonSide::SouthEast => UpsilonSide::NorthWest,
+            UpsilonSide::SouthWest => UpsilonSide::NorthEast,
+            UpsilonSide::NorthEast => UpsilonSide::SouthWest,
+            UpsilonSide::NorthWest => UpsilonSide::SouthEast,
+        }
+    }
+
+    /// Vrai pour les côtés qui relient deux octogones
+    pub fn is_diagonal(&self) -> bool {
+        matches!(
+            self,
+            UpsilonSide::SouthEast | UpsilonSide::SouthWest | UpsilonSide::NorthEast | UpsilonSide::NorthWest
+        )
+    }
+
+    /// Déplacement (dx, dy) vers la cellule voisine
+    fn offset(&self) -> (isize, isize) {
+        match self {
+            UpsilonSide::East => (1, 0),
+            UpsilonSide::West => (-1, 0),
+            UpsilonSide::South => (0, 1),
+            UpsilonSide::North => (0, -1),
+            UpsilonSide::SouthEast => (1, 1),
+            UpsilonSide::SouthWest => (-1, 1),
+            UpsilonSide::NorthEast => (1, -1),
+            UpsilonSide::NorthWest => (-1, -1),
+        }
+    }
+
+    /// Index du mur dans le stockage de la cellule, pour les côtés dont elle est propriétaire
+    fn owned_slot(&self) -> Option<usize> {
+        match self {
+            UpsilonSide::East => Some(0),
+            UpsilonSide::South => Some(1),
+            UpsilonSide::SouthEast => Some(2),
+            UpsilonSide::SouthWest => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// Mur modifié dans une grille upsilon
+/// `side` est toujours un côté possédé par la cellule (East, South, SouthEast ou SouthWest)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpsilonWallChange {
+    pub x: usize,
+    pub y: usize,
+    pub side: UpsilonSide,
+}
+
+/// Pavage "upsilon" d'octogones et de carrés, disposés en damier
+///
+/// Convention :
+/// - La cellule (x, y) est un octogone si (x + y) est pair, un carré sinon
+/// - Un carré a quatre voisins (des octogones) ; un octogone en a huit :
+///   quatre carrés sur ses côtés orthogonaux et quatre octogones en diagonale
+/// - Chaque cellule stocke ses murs East, South, SouthEast et SouthWest
+/// - Les bordures sont toujours considérées comme des murs
+/// - Une cellule (x,y) a l'index : y * width + x
+pub struct UpsilonGrid {
+    walls: Vec<[bool; 4]>,
+    width: usize,
+    height: usize,
+}
+
+/// Demi-largeur d'un octogone régulier quand les centres des cellules sont espacés de 1
+const OCTAGON_APOTHEM: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+/// Demi-côté commun aux carrés et aux octogones
+const HALF_SIDE: f64 = 1.0 - std::f64::consts::FRAC_1_SQRT_2;
+
+impl UpsilonGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            walls: vec![[false; 4]; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Index de la cellule (x, y) ; None hors de la grille
+    pub fn get_index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    /// Vrai si la cellule est un octogone ; faux hors de la grille
+    pub fn is_octagon(&self, cell: usize) -> bool {
+        cell < self.cell_count() && (cell % self.width + cell / self.width).is_multiple_of(2)
+    }
+
+    /// Cellule voisine dans une direction, si les deux cellules sont dans la grille
+    /// Les carrés n'ont pas de voisins en diagonale
+    pub fn neighbor(&self, cell: usize, side: UpsilonSide) -> Option<usize> {
+        if cell >= self.cell_count() || (side.is_diagonal() && !self.is_octagon(cell)) {
+            return None;
+        }
+
+        let (dx, dy) = side.offset();
+        let nx = (cell % self.width).checked_add_signed(dx)?;
+        let ny = (cell / self.width).checked_add_signed(dy)?;
+        self.get_index(nx, ny)
+    }
+
+    /// Vérifie la présence d'un mur sur un côté de la cellule (les bordures sont des murs)
+    /// Une cellule hors de la grille n'a aucun mur
+    pub fn has_wall(&self, cell: usize, side: UpsilonSide) -> bool {
+        if cell >= self.cell_count() {
+            return false;
+        }
+
+        match self.neighbor(cell, side) {
+            None => true,
+            Some(neighbor) => {
+                let (owner, slot) = self.wall_slot(cell, neighbor, side);
+                self.walls[owner][slot]
+            }
+        }
+    }
+
+    /// Cellule propriétaire et emplacement du mur situé sur `side` de `cell`
+    fn wall_slot(&self, cell: usize, neighbor: usize, side: UpsilonSide) -> (usize, usize) {
+        match side.owned_slot() {
+            Some(slot) => (cell, slot),
+            None => (neighbor, side.opposite().owned_slot().unwrap()),
+        }
+    }
+
+    fn side_towards(&self, from: usize, to: usize) -> Option<UpsilonSide> {
+        UpsilonSide::ALL.into_iter().find(|side| self.neighbor(from, *side) == Some(to))
+    }
+
+    /// Centre d'une cellule, décalé pour que les octogones du bord restent dans le dessin
+    fn center(&self, cell: usize) -> (f64, f64) {
+        let margin = OCTAGON_APOTHEM - 0.5;
+        (
+            (cell % self.width) as f64 + 0.5 + margin,
+            (cell / self.width) as f64 + 0.5 + margin,
+        )
+    }
+
+    /// Extrémités du côté `side` de la cellule
+    fn side_corners(&self, cell: usize, side: UpsilonSide) -> ((f64, f64), (f64, f64)) {
+        let (cx, cy) = self.center(cell);
+        let far = if self.is_octagon(cell) { OCTAGON_APOTHEM } else { HALF_SIDE };
+        let near = HALF_SIDE;
+
+        let ((x1, y1), (x2, y2)) = match side {
+            UpsilonSide::East => ((far, -near), (far, near)),
+            UpsilonSide::SouthEast => ((far, near), (near, far)),
+            UpsilonSide::South => ((near, far), (-near, far)),
+            UpsilonSide::SouthWest => ((-near, far), (-far, near)),
+            UpsilonSide::West => ((-far, near), (-far, -near)),
+            UpsilonSide::NorthWest => ((-far, -near), (-near, -far)),
+            UpsilonSide::North => ((-near, -far), (near, -far)),
+            UpsilonSide::NorthEast => ((near, -far), (far, -near)),
+        };
+        ((cx + x1, cy + y1), (cx + x2, cy + y2))
+    }
+}
+
+impl Topology for UpsilonGrid {
+    type Change = UpsilonWallChange;
+
+    fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        UpsilonSide::ALL.iter().filter_map(|side| self.neighbor(cell, *side)).collect()
+    }
+
+    fn is_linked(&self, from: usize, to: usize) -> bool {
+        match self.side_towards(from, to) {
+            Some(side) => !self.has_wall(from, side),
+            None => false,
+        }
+    }
+
+    fn link(&mut self, from: usize, to: usize) -> Option<UpsilonWallChange> {
+        let side = self.side_towards(from, to)?;
+        let (owner, slot) = self.wall_slot(from, to, side);
+        if !self.walls[owner][slot] {
+            return None;
+        }
+
+        self.walls[owner][slot] = false;
+        let owned_side = if owner == from { side } else { side.opposite() };
+        Some(UpsilonWallChange {
+            x: owner % self.width,
+            y: owner / self.width,
+            side: owned_side,
+        })
+    }
+
+    fn fill_walls(&mut self) {
+        self.walls.fill([true; 4]);
+    }
+
+    fn clear_walls(&mut self) {
+        self.walls.fill([false; 4]);
+    }
+
+    fn cell_point(&self, cell: usize) -> Point {
+        Point { x: cell % self.width, y: cell / self.width }
+    }
+}
+
+impl MazeGeometry for UpsilonGrid {
+    fn size(&self) -> (f64, f64) {
+        let margin = 2.0 * OCTAGON_APOTHEM - 1.0;
+        (self.width as f64 + margin, self.height as f64 + margin)
+    }
+
+    fn wall_segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+
+        for cell in 0..self.cell_count() {
+            let octagon = self.is_octagon(cell);
+            for side in UpsilonSide::ALL {
+                if side.is_diagonal() && !octagon {
+                    continue;
+                }
+
+                // Chaque mur intérieur n'est dessiné qu'une fois, par sa cellule propriétaire
+                let draw = match self.neighbor(cell, side) {
+                    None => true,
+                    Some(_) => side.owned_slot().is_some() && self.has_wall(cell, side),
+                };
+
+                if draw {
+                    let ((x1, y1), (x2, y2)) = self.side_corners(cell, side);
+                    segments.push(Segment { x1, y1, x2, y2 });
+                }
+            }
+        }
+
+        segments
+    }
+
+    fn cell_center(&self, cell: usize) -> (f64, f64) {
+        self.center(cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::check_topology;
+
+    #[test]
+    fn neighbors_and_links_are_consistent() {
+        for (width, height) in [(1, 1), (1, 4), (4, 1), (3, 3), (4, 5), (5, 4)] {
+            check_topology(&mut UpsilonGrid::new(width, height));
+        }
+    }
+
+    #[test]
+    fn octagons_and_squares_alternate() {
+        let grid = UpsilonGrid::new(3, 3);
+        let index = |x, y| grid.get_index(x, y).unwrap();
+        let count = |x, y| grid.neighbors(index(x, y)).len();
+
+        assert!(grid.is_octagon(index(0, 0)) && !grid.is_octagon(index(1, 0)));
+        // Octogones : coins, centre
+        assert_eq!(count(0, 0), 3);
+        assert_eq!(count(2, 2), 3);
+        assert_eq!(count(1, 1), 8);
+        // Carrés des bords : jamais de voisin en diagonale
+        assert_eq!(count(1, 0), 3);
+        assert_eq!(count(0, 1), 3);
+        assert_eq!(grid.neighbor(index(1, 0), UpsilonSide::SouthEast), None);
+    }
+
+    #[test]
+    fn link_reports_the_owned_wall() {
+        let mut grid = UpsilonGrid::new(3, 3);
+        grid.fill_walls();
+        let index = |x: usize, y: usize| y * 3 + x;
+
+        // Les diagonales nord appartiennent à l'octogone du dessus
+        let change = grid.link(index(1, 1), index(0, 0));
+        assert_eq!(change, Some(UpsilonWallChange { x: 0, y: 0, side: UpsilonSide::SouthEast }));
+        let change = grid.link(index(1, 1), index(2, 0));
+        assert_eq!(change, Some(UpsilonWallChange { x: 2, y: 0, side: UpsilonSide::SouthWest }));
+        assert!(!grid.has_wall(index(1, 1), UpsilonSide::NorthWest));
+        assert!(!grid.has_wall(index(2, 0), UpsilonSide::SouthWest));
+        assert!(grid.has_wall(index(1, 1), UpsilonSide::SouthEast));
+
+        // Les bordures restent des murs
+        grid.clear_walls();
+        assert!(grid.has_wall(index(0, 0), UpsilonSide::NorthWest));
+        assert!(grid.has_wall(index(1, 2), UpsilonSide::South));
+    }
+
+    #[test]
+    fn out_of_range_cells_have_no_walls_or_neighbors() {
+        let mut grid = UpsilonGrid::new(3, 3);
+        grid.fill_walls();
+        assert_eq!(grid.get_index(3, 0), None);
+        assert_eq!(grid.get_index(0, 3), None);
+        assert_eq!(grid.get_index(2, 2), Some(8));
+
+        for cell in [9, 10, usize::MAX] {
+            assert!(!grid.is_octagon(cell));
+            for side in UpsilonSide::ALL {
+                assert!(!grid.has_wall(cell, side));
+                assert_eq!(grid.neighbor(cell, side), None);
+            }
+            assert!(grid.neighbors(cell).is_empty());
+            assert_eq!(grid.link(8, cell), None);
+        }
+        assert!(grid.has_wall(8, UpsilonSide::South));
+        assert!(!UpsilonGrid::new(0, 0).is_octagon(0));
+    }
+}
@@ -0,0 +1,428 @@
+use crate::Point;
+use crate::error::MazeError;
+use crate::export::{self, PaperSize, PdfOptions};
+use crate::generators::{AlgorithmKind, GenerationResult, WallChange, WallOp, WallType};
+use crate::maze_generator::MazeGenerator;
+use crate::maze_grid::Crossing;
+use crate::replay::{Recording, ReplayPlayer};
+use crate::utils::set_panic_hook;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    fn alert(s: &str);
+}
+
+#[wasm_bindgen]
+pub fn greet() {
+    alert("Hello, Pathfinding!");
+}
+
+// Bindings JavaScript autour du générateur Rust
+// Exposé sous le nom "MazeGenerator" côté JS
+#[wasm_bindgen(js_name = MazeGenerator)]
+pub struct WasmMazeGenerator {
+    inner: MazeGenerator,
+}
+
+// Méthodes WASM
+// Les erreurs (MazeError) sont levées comme exceptions JS : une panique rendrait le module inutilisable
+#[wasm_bindgen(js_class = MazeGenerator)]
+impl WasmMazeGenerator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Result<WasmMazeGenerator, JsError> {
+        set_panic_hook();
+
+        Ok(WasmMazeGenerator {
+            inner: MazeGenerator::new(width, height)?,
+        })
+    }
+
+    // Vérifier s'il y a un mur vertical
+    pub fn has_vertical_wall(&self, x: usize, y: usize) -> Result<bool, JsError> {
+        self.inner.grid().check_point(x, y)?;
+        Ok(self.inner.has_vertical_wall(x, y))
+    }
+
+    // Vérifier s'il y a un mur horizontal
+    pub fn has_horizontal_wall(&self, x: usize, y: usize) -> Result<bool, JsError> {
+        self.inner.grid().check_point(x, y)?;
+        Ok(self.inner.has_horizontal_wall(x, y))
+    }
+
+    // Nettoyer la grille (supprimer tous les murs)
+    pub fn clear_grid(&mut self) {
+        self.inner.clear_grid()
+    }
+
+    // Remplir la grille de murs (labyrinthe plein)
+    pub fn fill_grid(&mut self) {
+        self.inner.fill_grid();
+    }
+
+    // Générer un labyrinthe complet instantanément
+    pub fn generate_maze(&mut self, algorithm: AlgorithmKind) {
+        self.inner.generate_maze(algorithm);
+    }
+
+    // Méthode unifiée pour démarrer la génération avec animation
+    pub fn start_generation(&mut self, algorithm: AlgorithmKind) {
+        self.inner.start_generation(algorithm);
+    }
+
+    // Démarrer une génération reproductible à partir d'une graine
+    pub fn start_generation_with_seed(&mut self, algorithm: AlgorithmKind, seed: u64) {
+        self.inner.start_generation_with_seed(algorithm, seed);
+    }
+
+    // Démarrer un labyrinthe tissé (Backtracking et Kruskal) ; retourne false si l'algorithme ne sait pas tisser
+    pub fn start_weave_generation(&mut self, algorithm: AlgorithmKind, seed: Option<u64>) -> bool {
+        self.inner.start_weave_generation(algorithm, seed)
+    }
+
+    // Croisement de la cellule, pour le rendu : 0 = aucun,
+    // 1 = tunnel ouest-est sous un couloir nord-sud, 2 = tunnel nord-sud sous un couloir ouest-est
+    pub fn get_crossing(&self, x: usize, y: usize) -> Result<u8, JsError> {
+        self.inner.grid().check_point(x, y)?;
+        Ok(match self.inner.grid().crossing(x, y) {
+            None => 0,
+            Some(Crossing::Horizontal) => 1,
+            Some(Crossing::Vertical) => 2,
+        })
+    }
+
+    // Effectuer une étape de génération
+    // Erreur si aucune génération n'a été démarrée (ou si la grille a été redimensionnée depuis)
+    pub fn generation_step(&mut self) -> Result<bool, JsError> {
+        let (result, _wall_changes) = self.inner.generation_step()?;
+        Ok(match result {
+            GenerationResult::Continue => false,
+            GenerationResult::Finished => true,
+        })
+    }
+
+    // Effectuer une étape de génération avec changements de murs
+    pub fn generation_step_with_changes(&mut self) -> Result<js_sys::Object, JsError> {
+        let (result, wall_changes) = self.inner.generation_step()?;
+
+        let is_finished = match result {
+            GenerationResult::Continue => false,
+            GenerationResult::Finished => true,
+        };
+
+        let result_obj = js_sys::Object::new();
+        set_property(&result_obj, "isFinished", &is_finished.into())?;
+
+        let changes_array = to_js_changes(wall_changes);
+        set_property(&result_obj, "changes", &changes_array)?;
+
+        Ok(result_obj)
+    }
+
+    // Effectuer jusqu'à `steps` étapes en un seul appel
+    // Retourne un Uint32Array plat : 4 valeurs par mur modifié (x, y, type, op) ;
+    // la fin de la génération se lit avec is_finished()
+    pub fn generation_steps(&mut self, steps: usize) -> Result<Vec<u32>, JsError> {
+        Ok(flatten_changes(&self.inner.generation_steps(steps)?.1))
+    }
+
+    // Enchaîner les étapes jusqu'à au moins `max_changes` murs modifiés ; même format que generation_steps
+    pub fn generation_run_for(&mut self, max_changes: usize) -> Result<Vec<u32>, JsError> {
+        Ok(flatten_changes(&self.inner.generation_run_for(max_changes)?.1))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    // Avancement de la génération en cours, entre 0 et 1 (barre de progression)
+    pub fn progress(&self) -> f64 {
+        self.inner.progress()
+    }
+
+    // Temps restant estimé, dans l'unité de `elapsed` (ex : millisecondes d'animation écoulées)
+    // undefined tant que la génération n'a pas avancé
+    pub fn estimate_remaining(&self, elapsed: f64) -> Option<f64> {
+        self.inner.estimate_remaining(elapsed)
+    }
+
+    // Obtenir les layers de cellules pour l'animation
+    // Retourne un tableau de tableaux : [[layer0_cells], [layer1_cells], ...]
+    pub fn get_cell_layers(&self) -> js_sys::Array {
+        to_js_layers(&self.inner.get_cell_layers())
+    }
+
+    // Changements des layers depuis l'appel précédent, dans un Uint32Array plat :
+    // [vider, puis (layer, x, y, entrée) pour chaque cellule], où vider = 1 demande d'effacer
+    // d'abord tous les layers affichés, et entrée = 1 si la cellule rejoint le layer, 0 si elle le quitte
+    pub fn take_layer_deltas(&mut self) -> Vec<u32> {
+        let (reset, deltas) = self.inner.take_layer_deltas();
+        let mut flat = Vec::with_capacity(1 + deltas.len() * 4);
+        flat.push(u32::from(reset));
+        for delta in deltas {
+            flat.extend([delta.layer as u32, delta.point.x as u32, delta.point.y as u32, u32::from(delta.entered)]);
+        }
+        flat
+    }
+
+    // Redimensionner la grille ; la génération en cours est abandonnée
+    pub fn resize_grid(&mut self, new_width: usize, new_height: usize) -> Result<(), JsError> {
+        Ok(self.inner.resize_grid(new_width, new_height)?)
+    }
+
+    // Raccorder les bords opposés : gauche/droite (horizontal) et/ou haut/bas (vertical)
+    // Les murs de raccord sont ceux de la dernière colonne et de la dernière ligne
+    pub fn set_wrap(&mut self, horizontal: bool, vertical: bool) {
+        self.inner.set_wrap(horizontal, vertical);
+    }
+
+    // Placer ou retirer un mur à la main (éditeur) ; retourne false si rien n'a changé
+    pub fn set_wall(&mut self, x: usize, y: usize, wall_type: WallType, present: bool) -> Result<bool, JsError> {
+        Ok(self.inner.set_wall(x, y, wall_type, present)?.is_some())
+    }
+
+    // Appliquer un changement reçu (synchronisation) ; retourne false si le mur était déjà dans cet état
+    pub fn apply_wall_change(&mut self, x: usize, y: usize, wall_type: WallType, op: WallOp) -> Result<bool, JsError> {
+        Ok(!self.inner.apply_changes(&[WallChange { x, y, wall_type, op }])?.is_empty())
+    }
+
+    // Accès direct aux murs dans la mémoire WASM : un bit par cellule d'index i = y * width + x,
+    // dans l'octet i >> 3 au bit i & 7
+    // Le pointeur n'est valable que jusqu'à la prochaine modification de la grille (redimensionnement...)
+    pub fn vertical_walls_ptr(&self) -> *const u8 {
+        self.inner.grid().wall_bytes(WallType::Vertical).as_ptr()
+    }
+
+    pub fn horizontal_walls_ptr(&self) -> *const u8 {
+        self.inner.grid().wall_bytes(WallType::Horizontal).as_ptr()
+    }
+
+    // Longueur en octets de chacun des deux tampons de murs
+    pub fn walls_len(&self) -> usize {
+        self.inner.grid().wall_bytes(WallType::Vertical).len()
+    }
+
+    // Vue Uint8Array sur les murs, sans copie
+    // À relire à chaque image : elle devient invalide dès que la mémoire WASM s'agrandit
+    pub fn vertical_walls_view(&self) -> js_sys::Uint8Array {
+        wall_view(self.inner.grid().wall_bytes(WallType::Vertical))
+    }
+
+    pub fn horizontal_walls_view(&self) -> js_sys::Uint8Array {
+        wall_view(self.inner.grid().wall_bytes(WallType::Horizontal))
+    }
+
+    // Suivi des tuiles modifiées (tile_size cellules de côté), pour ne redessiner qu'elles
+    pub fn enable_dirty_tracking(&mut self, tile_size: usize) {
+        self.inner.enable_dirty_tracking(tile_size);
+    }
+
+    pub fn disable_dirty_tracking(&mut self) {
+        self.inner.disable_dirty_tracking();
+    }
+
+    // Tuiles modifiées depuis l'appel précédent : Uint32Array plat, 4 valeurs par tuile
+    // (x, y, largeur, hauteur), en cellules
+    pub fn take_dirty_tiles(&mut self) -> Vec<u32> {
+        let tiles = self.inner.take_dirty_tiles();
+        let mut flat = Vec::with_capacity(tiles.len() * 4);
+        for tile in tiles {
+            flat.extend([tile.x as u32, tile.y as u32, tile.width as u32, tile.height as u32]);
+        }
+        flat
+    }
+
+    // Historique annuler / rétablir (désactivé par défaut)
+    pub fn set_history_enabled(&mut self, enabled: bool) {
+        self.inner.set_history_enabled(enabled);
+    }
+
+    // Annuler la dernière étape ; retourne les murs modifiés (WallChange[])
+    pub fn undo(&mut self) -> js_sys::Array {
+        to_js_changes(self.inner.undo())
+    }
+
+    // Rétablir l'étape suivante ; retourne les murs modifiés (WallChange[])
+    pub fn redo(&mut self) -> js_sys::Array {
+        to_js_changes(self.inner.redo())
+    }
+
+    // Se placer à une étape de l'historique ; retourne les murs modifiés (WallChange[])
+    pub fn seek(&mut self, step: usize) -> js_sys::Array {
+        to_js_changes(self.inner.seek(step))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.inner.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.inner.can_redo()
+    }
+
+    pub fn history_length(&self) -> usize {
+        self.inner.history_len()
+    }
+
+    pub fn history_position(&self) -> usize {
+        self.inner.history_position()
+    }
+
+    // Enregistrer les étapes de génération à partir de l'état actuel (après start_generation)
+    pub fn start_recording(&mut self) {
+        self.inner.start_recording();
+    }
+
+    // Arrêter l'enregistrement et le retourner au format binaire (vide si rien n'était enregistré)
+    pub fn stop_recording(&mut self) -> Vec<u8> {
+        self.inner.stop_recording().map(|recording| recording.to_bytes()).unwrap_or_default()
+    }
+
+    // Instantané binaire de la grille et de la génération en cours, à conserver (ex : localStorage)
+    // pour reprendre plus tard avec restore
+    pub fn snapshot(&self) -> Result<Vec<u8>, JsError> {
+        Ok(self.inner.snapshot()?)
+    }
+
+    // Recharger un instantané ; la génération reprend là où elle s'était arrêtée
+    // La grille peut changer de dimensions : tout est à redessiner
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<(), JsError> {
+        Ok(self.inner.restore(bytes)?)
+    }
+
+    // Algorithme de la génération en cours (undefined si aucune n'a été démarrée)
+    pub fn algorithm_kind(&self) -> Option<AlgorithmKind> {
+        self.inner.algorithm_kind()
+    }
+
+    // Exporter une fiche imprimable (labyrinthe + corrigé) au format PDF
+    pub fn export_pdf(&self, page_width_mm: f64, page_height_mm: f64, margin_mm: f64) -> Result<Vec<u8>, JsError> {
+        let options = PdfOptions {
+            paper: PaperSize::Custom { width_mm: page_width_mm, height_mm: page_height_mm },
+            margin_mm,
+            title: match self.inner.algorithm_name() {
+                Some(name) => format!("Labyrinthe {} x {} - {}", self.inner.get_grid_width(), self.inner.get_grid_height(), name),
+                None => format!("Labyrinthe {} x {}", self.inner.get_grid_width(), self.inner.get_grid_height()),
+            },
+            ..PdfOptions::default()
+        };
+
+        Ok(export::export_pdf(self.inner.grid(), &options)?)
+    }
+
+    // Obtenir les dimensions actuelles
+    pub fn get_grid_width(&self) -> usize {
+        self.inner.get_grid_width()
+    }
+
+    pub fn get_grid_height(&self) -> usize {
+        self.inner.get_grid_height()
+    }
+}
+
+// Relecture d'un enregistrement, sans relancer l'algorithme
+#[wasm_bindgen(js_name = ReplayPlayer)]
+pub struct WasmReplayPlayer {
+    inner: ReplayPlayer,
+}
+
+#[wasm_bindgen(js_class = ReplayPlayer)]
+impl WasmReplayPlayer {
+    // Charger un enregistrement produit par MazeGenerator.stop_recording
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<WasmReplayPlayer, JsError> {
+        let recording = Recording::from_bytes(bytes).map_err(MazeError::from)?;
+        Ok(WasmReplayPlayer { inner: ReplayPlayer::new(recording) })
+    }
+
+    // Appliquer jusqu'à `steps` étapes ; retourne les murs modifiés (WallChange[])
+    pub fn advance(&mut self, steps: usize) -> js_sys::Array {
+        to_js_changes(self.inner.advance(steps))
+    }
+
+    // Se placer après `position` étapes ; retourne les murs modifiés (WallChange[])
+    pub fn seek(&mut self, position: usize) -> js_sys::Array {
+        to_js_changes(self.inner.seek(position))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    pub fn position(&self) -> usize {
+        self.inner.position()
+    }
+
+    pub fn length(&self) -> usize {
+        self.inner.recording().len()
+    }
+
+    pub fn has_vertical_wall(&self, x: usize, y: usize) -> Result<bool, JsError> {
+        self.inner.grid().check_point(x, y)?;
+        Ok(self.inner.grid().has_wall(x, y, WallType::Vertical))
+    }
+
+    pub fn has_horizontal_wall(&self, x: usize, y: usize) -> Result<bool, JsError> {
+        self.inner.grid().check_point(x, y)?;
+        Ok(self.inner.grid().has_wall(x, y, WallType::Horizontal))
+    }
+
+    // Layers d'animation de la dernière étape appliquée
+    pub fn get_cell_layers(&self) -> js_sys::Array {
+        to_js_layers(self.inner.cell_layers())
+    }
+
+    pub fn get_grid_width(&self) -> usize {
+        self.inner.grid().width()
+    }
+
+    pub fn get_grid_height(&self) -> usize {
+        self.inner.grid().height()
+    }
+}
+
+// Convertir une liste de murs modifiés en tableau JS
+// Utilise les structures Rust générées par wasm-bindgen
+fn to_js_changes(wall_changes: Vec<WallChange>) -> js_sys::Array {
+    let changes_array = js_sys::Array::new();
+    for change in wall_changes {
+        changes_array.push(&JsValue::from(change));
+    }
+    changes_array
+}
+
+// Définir une propriété d'un objet JS
+fn set_property(object: &js_sys::Object, key: &str, value: &JsValue) -> Result<(), JsError> {
+    js_sys::Reflect::set(object, &key.into(), value)
+        .map(|_| ())
+        .map_err(|error| JsError::new(&format!("Propriété {} non définie : {:?}", key, error)))
+}
+
+// Aplatir les murs modifiés en (x, y, type, op) pour un seul transfert vers JS
+fn flatten_changes(wall_changes: &[WallChange]) -> Vec<u32> {
+    let mut flat = Vec::with_capacity(wall_changes.len() * 4);
+    for change in wall_changes {
+        flat.extend([change.x as u32, change.y as u32, change.wall_type as u32, change.op as u32]);
+    }
+    flat
+}
+
+// Vue JS sur un tampon de la mémoire WASM
+fn wall_view(bytes: &[u8]) -> js_sys::Uint8Array {
+    // SAFETY: la vue est rendue immédiatement à JS, qui doit la relire avant toute
+    // nouvelle allocation côté Rust (voir vertical_walls_view)
+    unsafe { js_sys::Uint8Array::view(bytes) }
+}
+
+// Convertir des layers de cellules en tableau de tableaux de Point
+fn to_js_layers(layers: &[Vec<Point>]) -> js_sys::Array {
+    let layers_array = js_sys::Array::new();
+    for layer in layers {
+        let layer_array = js_sys::Array::new();
+        for point in layer {
+            layer_array.push(&JsValue::from(*point));
+        }
+        layers_array.push(&layer_array);
+    }
+    layers_array
+}
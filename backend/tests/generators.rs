@@ -0,0 +1,421 @@
+//! Invariants des générateurs, vérifiés sur des tailles, graines et raccords de bords aléatoires
+
+use pathfinding::generators::{
+    BacktrackingGenerator, KruskalGenerator, PrimGenerator, RecursiveDivisionGenerator, WilsonGenerator,
+};
+use pathfinding::maze_generator::MAX_DIMENSION;
+use pathfinding::replay::ReplayPlayer;
+use pathfinding::{
+    AlgorithmKind, DeltaGrid, GenerationAlgorithm, GenerationResult, HexGrid, LayeredGrid, MazeError, MazeGenerator,
+    MazeGrid, PolarGrid, Topology, UpsilonGrid, WallChange, WallOp, WallType, export,
+};
+use proptest::prelude::*;
+
+// Garde-fou contre une génération qui ne se terminerait jamais
+const MAX_STEPS: usize = 1_000_000;
+
+fn copy(grid: &MazeGrid) -> MazeGrid {
+    export::from_bytes(&export::to_bytes(grid)).unwrap()
+}
+
+fn sorted(mut changes: Vec<WallChange>) -> Vec<WallChange> {
+    changes.sort_by_key(|change| (change.y, change.x, change.wall_type as u8));
+    changes
+}
+
+// Racine d'une cellule dans la forêt union-find
+fn find(parents: &mut [usize], mut cell: usize) -> usize {
+    while parents[cell] != cell {
+        parents[cell] = parents[parents[cell]];
+        cell = parents[cell];
+    }
+    cell
+}
+
+/// Labyrinthe parfait : n − 1 passages, sans cycle, toutes les cellules reliées
+fn assert_perfect(grid: &MazeGrid) -> Result<(), TestCaseError> {
+    let cell_count = grid.cell_count();
+    let mut parents: Vec<usize> = (0..cell_count).collect();
+    let mut passages = 0;
+
+    for cell in 0..cell_count {
+        for neighbor in grid.passages(cell) {
+            prop_assert!(grid.passages(neighbor).contains(&cell), "passage à sens unique {} -> {}", cell, neighbor);
+            if cell < neighbor {
+                passages += 1;
+                let (a, b) = (find(&mut parents, cell), find(&mut parents, neighbor));
+                prop_assert_ne!(a, b, "cycle par le passage {} -> {}", cell, neighbor);
+                parents[a] = b;
+            }
+        }
+    }
+
+    prop_assert_eq!(passages, cell_count - 1);
+    let root = find(&mut parents, 0);
+    for cell in 0..cell_count {
+        prop_assert_eq!(find(&mut parents, cell), root, "cellule {} isolée", cell);
+    }
+    Ok(())
+}
+
+/// Déroule une génération en vérifiant chaque étape, puis le labyrinthe obtenu
+fn check_generation(mut generator: MazeGenerator) -> Result<(), TestCaseError> {
+    let (width, height) = (generator.get_grid_width(), generator.get_grid_height());
+    let mut progress = generator.progress();
+
+    for _ in 0..MAX_STEPS {
+        let before = copy(generator.grid());
+        let (result, changes) = generator.generation_step().unwrap();
+
+        // Fin de génération signalée de façon cohérente
+        prop_assert_eq!(result == GenerationResult::Finished, generator.is_finished());
+
+        // Murs modifiés dans la grille, et identiques au diff réel
+        for change in &changes {
+            prop_assert!(change.x < width && change.y < height, "mur hors de la grille : {:?}", change);
+        }
+        let diff = before.diff(generator.grid()).unwrap();
+        prop_assert_eq!(sorted(changes), diff);
+
+        // Layers d'animation dans la grille
+        for layer in generator.get_cell_layers() {
+            for point in layer {
+                prop_assert!(generator.grid().is_valid_point(point.x, point.y), "point hors de la grille : {:?}", point);
+            }
+        }
+
+        // Avancement croissant, entre 0 et 1, et complet à la fin
+        prop_assert!(generator.progress() >= progress && generator.progress() <= 1.0, "avancement {} après {}", generator.progress(), progress);
+        progress = generator.progress();
+
+        if result == GenerationResult::Finished {
+            prop_assert_eq!(progress, 1.0);
+            return assert_perfect(generator.grid());
+        }
+    }
+
+    Err(TestCaseError::fail(format!("génération non terminée après {MAX_STEPS} étapes")))
+}
+
+fn algorithm() -> impl Strategy<Value = AlgorithmKind> {
+    prop::sample::select(AlgorithmKind::ALL.to_vec())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn generates_perfect_mazes(
+        kind in algorithm(),
+        width in 1usize..20,
+        height in 1usize..20,
+        seed in any::<u64>(),
+    ) {
+        let mut generator = MazeGenerator::new(width, height).unwrap();
+        generator.start_generation_with_seed(kind, seed);
+        check_generation(generator)?;
+    }
+
+    #[test]
+    fn generates_perfect_wrapped_mazes(
+        kind in algorithm(),
+        width in 1usize..15,
+        height in 1usize..15,
+        wrap in any::<(bool, bool)>(),
+        seed in any::<u64>(),
+    ) {
+        let mut generator = MazeGenerator::new(width, height).unwrap();
+        generator.set_wrap(wrap.0, wrap.1);
+        generator.start_generation_with_seed(kind, seed);
+        check_generation(generator)?;
+    }
+
+    // Les tunnels relient les cellules sans ouvrir de cycle : le labyrinthe reste parfait
+    #[test]
+    fn generates_perfect_weave_mazes(
+        kind in prop::sample::select(vec![AlgorithmKind::Backtracking, AlgorithmKind::Kruskal]),
+        width in 1usize..15,
+        height in 1usize..15,
+        seed in any::<u64>(),
+    ) {
+        let mut generator = MazeGenerator::new(width, height).unwrap();
+        prop_assert!(generator.start_weave_generation(kind, Some(seed)));
+        check_generation(generator)?;
+    }
+
+    // Une même graine redonne le même labyrinthe
+    #[test]
+    fn seeded_generation_is_reproducible(kind in algorithm(), width in 1usize..15, height in 1usize..15, seed in any::<u64>()) {
+        let mazes: Vec<Vec<u8>> = (0..2)
+            .map(|_| {
+                let mut generator = MazeGenerator::new(width, height).unwrap();
+                generator.start_generation_with_seed(kind, seed);
+                while !generator.is_finished() {
+                    generator.generation_step().unwrap();
+                }
+                export::to_bytes(generator.grid())
+            })
+            .collect();
+        prop_assert_eq!(&mazes[0], &mazes[1]);
+    }
+
+    // Une génération reprise depuis un instantané se poursuit à l'identique, tissage et raccords compris
+    #[test]
+    fn snapshot_resumes_generation(
+        kind in algorithm(),
+        width in 1usize..15,
+        height in 1usize..15,
+        wrap in any::<(bool, bool)>(),
+        weave in any::<bool>(),
+        seed in any::<u64>(),
+        steps in 0usize..400,
+    ) {
+        let mut original = start(kind, width, height, wrap, weave, seed);
+        original.generation_steps(steps).unwrap();
+
+        let mut resumed = MazeGenerator::new(1, 1).unwrap();
+        resumed.restore(&original.snapshot().unwrap()).unwrap();
+        prop_assert_eq!(resumed.algorithm_kind(), Some(kind));
+        prop_assert_eq!(resumed.progress(), original.progress());
+        prop_assert_eq!(resumed.get_cell_layers(), original.get_cell_layers());
+
+        for _ in 0..MAX_STEPS {
+            let step = original.generation_step().unwrap();
+            prop_assert_eq!(resumed.generation_step().unwrap(), step.clone());
+            if step.0 == GenerationResult::Finished {
+                break;
+            }
+        }
+        prop_assert_eq!(resumed.snapshot().unwrap(), original.snapshot().unwrap());
+    }
+
+    // Un instantané corrompu est refusé, ou donne un état dont la génération ne panique pas
+    #[test]
+    fn corrupt_snapshot_does_not_panic(
+        kind in algorithm(),
+        weave in any::<bool>(),
+        seed in any::<u64>(),
+        steps in 0usize..200,
+        corruption in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..4),
+        truncate in any::<Option<prop::sample::Index>>(),
+    ) {
+        let mut original = start(kind, 8, 6, (false, false), weave, seed);
+        original.generation_steps(steps).unwrap();
+
+        let mut bytes = original.snapshot().unwrap();
+        for (index, value) in corruption {
+            let index = index.index(bytes.len());
+            bytes[index] = value;
+        }
+        if let Some(len) = truncate {
+            bytes.truncate(len.index(bytes.len()));
+        }
+
+        let mut restored = MazeGenerator::new(1, 1).unwrap();
+        if restored.restore(&bytes).is_ok() && restored.algorithm_kind().is_some() {
+            for _ in 0..10_000 {
+                if restored.generation_step().unwrap().0 == GenerationResult::Finished {
+                    break;
+                }
+                restored.get_cell_layers();
+                restored.progress();
+            }
+        }
+    }
+}
+
+// Revenir en arrière dans l'historique abandonne la génération : reprendre l'ancien état
+// de l'algorithme sur la grille rembobinée donnerait un labyrinthe invalide
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(50))]
+
+    #[test]
+    fn history_rewind_abandons_generation(
+        kind in algorithm(),
+        width in 2usize..10,
+        height in 2usize..10,
+        seed in any::<u64>(),
+        steps in 1usize..60,
+        back in 1usize..20,
+        use_seek in any::<bool>(),
+    ) {
+        let mut generator = start(kind, width, height, (false, false), false, seed);
+        generator.set_history_enabled(true);
+        generator.generation_steps(steps).unwrap();
+
+        let position = generator.history_position();
+        prop_assume!(position > 0);
+        if use_seek {
+            generator.seek(position.saturating_sub(back));
+        } else {
+            generator.undo();
+        }
+
+        prop_assert_eq!(generator.generation_step().err(), Some(MazeError::NotStarted));
+        prop_assert!(!generator.is_finished());
+        prop_assert!(generator.get_cell_layers().is_empty());
+
+        // Une nouvelle génération repart de la grille rembobinée et donne un labyrinthe parfait
+        generator.start_generation_with_seed(kind, seed);
+        check_generation(generator)?;
+    }
+}
+
+fn start(kind: AlgorithmKind, width: usize, height: usize, wrap: (bool, bool), weave: bool, seed: u64) -> MazeGenerator {
+    let mut generator = MazeGenerator::new(width, height).unwrap();
+    generator.set_wrap(wrap.0, wrap.1);
+    if !(weave && generator.start_weave_generation(kind, Some(seed))) {
+        generator.start_generation_with_seed(kind, seed);
+    }
+    generator
+}
+
+// Les erreurs de l'API sont retournées, sans panique
+#[test]
+fn reports_errors_instead_of_panicking() {
+    assert_eq!(MazeGenerator::new(0, 5).err(), Some(MazeError::InvalidDimensions { width: 0, height: 5 }));
+    assert!(MazeGenerator::new(MAX_DIMENSION + 1, 1).is_err());
+
+    let mut generator = MazeGenerator::new(4, 4).unwrap();
+    assert_eq!(generator.generation_step().err(), Some(MazeError::NotStarted));
+    assert_eq!(generator.set_wall(4, 0, WallType::Vertical, true).err(), Some(MazeError::OutOfBounds { x: 4, y: 0 }));
+
+    // Un redimensionnement abandonne la génération en cours
+    generator.start_generation(AlgorithmKind::Wilson);
+    assert!(generator.generation_step().is_ok());
+    assert!(generator.resize_grid(0, 0).is_err());
+    assert_eq!((generator.get_grid_width(), generator.get_grid_height()), (4, 4));
+    generator.resize_grid(6, 3).unwrap();
+    assert_eq!(generator.generation_steps(10).err(), Some(MazeError::NotStarted));
+
+    assert!(matches!(export::from_bytes(b"MAZE").map_err(MazeError::from), Err(MazeError::Decode(_))));
+}
+
+// Un redimensionnement abandonne l'enregistrement, dont l'état initial n'a plus la bonne taille
+#[test]
+fn resize_stops_recording() {
+    let mut generator = MazeGenerator::new(5, 5).unwrap();
+    generator.start_generation_with_seed(AlgorithmKind::Backtracking, 1);
+    generator.start_recording();
+    generator.generation_steps(10).unwrap();
+    generator.take_layer_deltas();
+
+    generator.resize_grid(8, 3).unwrap();
+    assert!(!generator.is_recording());
+    assert!(generator.stop_recording().is_none());
+
+    // Les layers déjà transmis sont oubliés : le prochain appel demande d'effacer l'affichage
+    assert_eq!(generator.take_layer_deltas(), (true, Vec::new()));
+
+    // Un nouvel enregistrement part bien de la nouvelle grille
+    generator.start_generation_with_seed(AlgorithmKind::Backtracking, 1);
+    generator.start_recording();
+    generator.generation_steps(5).unwrap();
+    let recording = generator.stop_recording().unwrap();
+    assert_eq!(recording.initial_grid().width, 8);
+}
+
+// Raccorder les bords change les voisinages : la génération en cours, l'enregistrement et l'historique sont abandonnés
+#[test]
+fn wrap_change_abandons_generation() {
+    let mut generator = MazeGenerator::new(6, 6).unwrap();
+    generator.set_history_enabled(true);
+    generator.start_generation_with_seed(AlgorithmKind::Kruskal, 2);
+    generator.start_recording();
+    generator.generation_steps(10).unwrap();
+    assert!(generator.history_len() > 0);
+
+    // Sans effet sur les voisinages : rien n'est abandonné
+    generator.set_wrap(false, false);
+    assert!(generator.is_recording() && generator.generation_step().is_ok());
+
+    generator.set_wrap(true, false);
+    assert_eq!(generator.generation_step().err(), Some(MazeError::NotStarted));
+    assert!(!generator.is_recording());
+    assert_eq!(generator.history_len(), 0);
+    assert_eq!(generator.take_layer_deltas(), (true, Vec::new()));
+
+    // Une nouvelle génération utilise bien le raccord
+    generator.generate_maze(AlgorithmKind::Kruskal);
+    assert!(generator.grid().wraps_horizontally());
+}
+
+// Une modification hors génération abandonne l'enregistrement : sa relecture ne la reproduirait pas
+#[test]
+fn manual_edits_stop_recording() {
+    let edits: [fn(&mut MazeGenerator); 6] = [
+        |generator| generator.clear_grid(),
+        |generator| generator.fill_grid(),
+        |generator| assert!(generator.set_wall(1, 1, WallType::Vertical, false).unwrap().is_some()),
+        |generator| {
+            let change = WallChange { x: 1, y: 1, wall_type: WallType::Horizontal, op: WallOp::Removed };
+            assert_eq!(generator.apply_changes(&[change]).unwrap().len(), 1);
+        },
+        |generator| assert!(!generator.undo().is_empty()),
+        |generator| generator.start_generation(AlgorithmKind::Prim),
+    ];
+
+    for edit in edits {
+        let mut generator = MazeGenerator::new(5, 5).unwrap();
+        generator.set_history_enabled(true);
+        generator.fill_grid();
+        generator.start_recording();
+        edit(&mut generator);
+        assert!(generator.stop_recording().is_none());
+    }
+
+    // Une édition sans effet ne l'interrompt pas
+    let mut generator = MazeGenerator::new(5, 5).unwrap();
+    generator.fill_grid();
+    generator.start_recording();
+    assert_eq!(generator.set_wall(1, 1, WallType::Vertical, true).unwrap(), None);
+    assert!(generator.is_recording());
+
+    // Relecture fidèle d'une génération enregistrée jusqu'au bout
+    generator.start_generation_with_seed(AlgorithmKind::Prim, 3);
+    generator.start_recording();
+    while !generator.is_finished() {
+        generator.generation_step().unwrap();
+    }
+    let mut player = ReplayPlayer::new(generator.stop_recording().unwrap());
+    player.advance(usize::MAX);
+    assert_eq!(player.grid().diff(generator.grid()), Some(Vec::new()));
+}
+
+// Sur une topologie sans cellule, les générateurs sont terminés dès le démarrage
+fn check_empty<G: Topology>(mut grid: G) {
+    let generators: [Box<dyn GenerationAlgorithm<G>>; 4] = [
+        Box::new(BacktrackingGenerator::with_seed(1)),
+        Box::new(PrimGenerator::with_seed(1)),
+        Box::new(KruskalGenerator::with_seed(1)),
+        Box::new(WilsonGenerator::with_seed(1)),
+    ];
+    for mut generator in generators {
+        generator.start(&mut grid);
+        assert!(generator.is_finished(), "{} non terminé", generator.get_name());
+        assert_eq!(generator.progress(), 1.0);
+        assert!(generator.get_cell_layers(&grid).iter().all(Vec::is_empty));
+        let (result, changes) = generator.step(&mut grid);
+        assert_eq!(result, GenerationResult::Finished);
+        assert!(changes.is_empty());
+    }
+}
+
+#[test]
+fn empty_topologies_are_already_finished() {
+    check_empty(MazeGrid::new(0, 3));
+    check_empty(HexGrid::new(0, 0));
+    check_empty(DeltaGrid::new(4, 0));
+    check_empty(PolarGrid::new(0));
+    check_empty(UpsilonGrid::new(0, 2));
+    check_empty(LayeredGrid::new(3, 3, 0));
+
+    for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+        let mut grid = MazeGrid::new(width, height);
+        let mut generator = RecursiveDivisionGenerator::with_seed(1);
+        generator.start(&mut grid);
+        assert!(generator.is_finished());
+        assert!(generator.get_cell_layers(&grid).is_empty());
+        assert_eq!(generator.step(&mut grid).0, GenerationResult::Finished);
+    }
+}
@@ -0,0 +1,175 @@
+//! Uniformité des générateurs sur de toutes petites grilles
+//!
+//! Tous les arbres couvrants de la grille sont énumérés, puis chaque générateur est lancé
+//! des milliers de fois avec des graines différentes ; un test du khi-deux compare la
+//! distribution obtenue à la loi uniforme. Wilson doit être uniforme ; pour les autres
+//! algorithmes, le biais est seulement mesuré et affiché :
+//!
+//! `cargo test --release --test uniformity -- --nocapture --include-ignored`
+
+use pathfinding::{AlgorithmKind, MazeGenerator, WallType};
+use std::collections::HashMap;
+
+// Quantile à 99,9 % de la loi normale : un générateur uniforme échoue une fois sur mille
+const Z_999: f64 = 3.090;
+
+/// Arêtes de la grille : (cellule, mur qui la sépare de son voisin de droite ou du dessous)
+fn edges(width: usize, height: usize) -> Vec<(usize, usize, WallType)> {
+    let mut edges = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if x + 1 < width {
+                edges.push((x, y, WallType::Vertical));
+            }
+            if y + 1 < height {
+                edges.push((x, y, WallType::Horizontal));
+            }
+        }
+    }
+    edges
+}
+
+// Racine d'une cellule dans la forêt union-find
+fn find(parents: &mut [usize], mut cell: usize) -> usize {
+    while parents[cell] != cell {
+        parents[cell] = parents[parents[cell]];
+        cell = parents[cell];
+    }
+    cell
+}
+
+/// Tous les arbres couvrants, sous forme de masques des arêtes ouvertes
+fn spanning_trees(width: usize, height: usize) -> Vec<u32> {
+    let edges = edges(width, height);
+    let cell_count = width * height;
+    assert!(edges.len() < 32, "grille trop grande pour l'énumération");
+
+    (0u32..1 << edges.len())
+        .filter(|mask| mask.count_ones() as usize == cell_count - 1)
+        .filter(|&mask| {
+            // n − 1 arêtes sans cycle : c'est un arbre couvrant
+            let mut parents: Vec<usize> = (0..cell_count).collect();
+            edges.iter().enumerate().filter(|(bit, _)| mask & (1 << bit) != 0).all(|(_, &(x, y, wall_type))| {
+                let cell = y * width + x;
+                let neighbor = match wall_type {
+                    WallType::Vertical => cell + 1,
+                    WallType::Horizontal => cell + width,
+                };
+                let (a, b) = (find(&mut parents, cell), find(&mut parents, neighbor));
+                parents[a] = b;
+                a != b
+            })
+        })
+        .collect()
+}
+
+/// Masque des arêtes ouvertes d'un labyrinthe généré
+fn generate_tree(kind: AlgorithmKind, width: usize, height: usize, seed: u64) -> u32 {
+    let mut generator = MazeGenerator::new(width, height).unwrap();
+    generator.start_generation_with_seed(kind, seed);
+    while !generator.is_finished() {
+        generator.generation_step().unwrap();
+    }
+
+    edges(width, height)
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(x, y, wall_type))| !generator.grid().has_wall(x, y, wall_type))
+        .fold(0, |mask, (bit, _)| mask | 1 << bit)
+}
+
+struct Report {
+    trees: usize,
+    runs: usize,
+    chi_square: f64,
+    critical: f64,
+    // Distance en variation totale à la loi uniforme (0 = uniforme, 1 = disjointe)
+    total_variation: f64,
+    // Fréquences extrêmes, rapportées à la fréquence attendue
+    min_ratio: f64,
+    max_ratio: f64,
+}
+
+impl Report {
+    fn is_uniform(&self) -> bool {
+        self.chi_square < self.critical
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} arbres, {} tirages : khi² = {:.1} (seuil {:.1}), variation totale = {:.3}, fréquences de {:.2} à {:.2} fois l'attendu",
+            self.trees, self.runs, self.chi_square, self.critical, self.total_variation, self.min_ratio, self.max_ratio
+        )
+    }
+}
+
+/// Lance le générateur `runs_per_tree` fois par arbre possible et compare à la loi uniforme
+fn measure(kind: AlgorithmKind, width: usize, height: usize, runs_per_tree: usize) -> Report {
+    let trees = spanning_trees(width, height);
+    let runs = trees.len() * runs_per_tree;
+
+    let mut counts: HashMap<u32, usize> = trees.iter().map(|&tree| (tree, 0)).collect();
+    for seed in 0..runs as u64 {
+        let tree = generate_tree(kind, width, height, seed);
+        *counts.get_mut(&tree).unwrap_or_else(|| panic!("{kind:?} : labyrinthe qui n'est pas un arbre couvrant")) += 1;
+    }
+
+    let expected = runs_per_tree as f64;
+    let chi_square = counts.values().map(|&count| (count as f64 - expected).powi(2) / expected).sum();
+    let total_variation = counts.values().map(|&count| (count as f64 - expected).abs()).sum::<f64>() / (2.0 * runs as f64);
+    let min_ratio = *counts.values().min().unwrap() as f64 / expected;
+    let max_ratio = *counts.values().max().unwrap() as f64 / expected;
+
+    // Quantile du khi-deux par l'approximation de Wilson-Hilferty
+    let df = (trees.len() - 1) as f64;
+    let critical = df * (1.0 - 2.0 / (9.0 * df) + Z_999 * (2.0 / (9.0 * df)).sqrt()).powi(3);
+
+    Report { trees: trees.len(), runs, chi_square, critical, total_variation, min_ratio, max_ratio }
+}
+
+#[test]
+fn enumerates_all_spanning_trees() {
+    // Nombres connus d'arbres couvrants des grilles 2×2, 3×3 et 3×4
+    assert_eq!(spanning_trees(2, 2).len(), 4);
+    assert_eq!(spanning_trees(3, 3).len(), 192);
+    assert_eq!(spanning_trees(3, 4).len(), 2415);
+}
+
+#[test]
+fn wilson_is_uniform_on_3x3() {
+    let report = measure(AlgorithmKind::Wilson, 3, 3, 100);
+    println!("Wilson 3×3 : {report}");
+    assert!(report.is_uniform(), "Wilson 3×3 n'est pas uniforme : {report}");
+}
+
+// Plus long : plus de 100 000 générations
+#[test]
+#[ignore]
+fn wilson_is_uniform_on_3x4() {
+    let report = measure(AlgorithmKind::Wilson, 3, 4, 50);
+    println!("Wilson 3×4 : {report}");
+    assert!(report.is_uniform(), "Wilson 3×4 n'est pas uniforme : {report}");
+}
+
+// Les autres algorithmes ne promettent pas l'uniformité : leur biais est seulement affiché
+#[test]
+fn report_bias_on_3x3() {
+    for kind in AlgorithmKind::ALL {
+        let report = measure(kind, 3, 3, 100);
+        let verdict = if report.is_uniform() { "compatible avec l'uniforme" } else { "biaisé" };
+        println!("{kind:?} 3×3 ({verdict}) : {report}");
+    }
+}
+
+#[test]
+#[ignore]
+fn report_bias_on_3x4() {
+    for kind in AlgorithmKind::ALL {
+        let report = measure(kind, 3, 4, 50);
+        let verdict = if report.is_uniform() { "compatible avec l'uniforme" } else { "biaisé" };
+        println!("{kind:?} 3×4 ({verdict}) : {report}");
+    }
+}
@@ -11,6 +11,7 @@ function App() {
   const [isGenerating, setIsGenerating] = useState(false)
   const [isPaused, setIsPaused] = useState(false)
   const [speed, setSpeed] = useState(50)
+  const [progress, setProgress] = useState(mazeService.getProgress())
   const [selectedAlgorithm, setSelectedAlgorithm] = useState<AlgorithmKind>(AlgorithmKind.Backtracking)
   const [generator, setGenerator] = useState<MazeGenerator | null>(null)
   const [dimensionsKey, setDimensionsKey] = useState(0) // Pour forcer le re-render
@@ -24,6 +25,16 @@ function App() {
       .then((gen) => {
         setGenerator(gen)
         setLoading(false)
+
+        // Génération interrompue lors d'une visite précédente : la reprendre en pause
+        const restored = mazeService.restoreSnapshot()
+        if (restored !== null) {
+          setSelectedAlgorithm(restored)
+          setProgress(mazeService.getProgress())
+          setIsGenerating(true)
+          setIsPaused(true)
+          setDimensionsKey(prev => prev + 1)
+        }
       })
       .catch((err) => {
         setError(`Erreur de chargement: ${err.message}`)
@@ -46,6 +57,7 @@ function App() {
 
     mazeService.prepareGeneration(selectedAlgorithm)
     mazeRendererRef.current.drawFullGrid()
+    setProgress(mazeService.getProgress())
     setIsGenerating(true)
     handleResume()
   }
@@ -61,16 +73,13 @@ function App() {
     mazeService.startAnimation(
       // onStep - mêmes callbacks qu'au démarrage
       (changes) => {
-        // Recursive Division AJOUTE des murs, les autres SUPPRIMENT des murs
-        if (selectedAlgorithm === AlgorithmKind.RecursiveDivision) {
-          mazeRendererRef.current?.addWallChanges(changes)
-        } else {
-          mazeRendererRef.current?.deleteWallChanges(changes)
-        }
+        mazeRendererRef.current?.drawFlatChanges(changes)
         mazeRendererRef.current?.drawCurrentCell()
+        setProgress(mazeService.getProgress())
       },
       // onComplete
       () => {
+        mazeService.clearSnapshot()
         mazeRendererRef.current?.drawFullGrid()
         setIsGenerating(false)
         setIsPaused(false)
@@ -82,6 +91,7 @@ function App() {
 
   const handleStop = () => {
     mazeService.stopAnimation()
+    mazeService.clearSnapshot()
     setIsGenerating(false)
     setIsPaused(false)
   }
@@ -138,6 +148,8 @@ function App() {
           <MazeControls
             isGenerating={isGenerating}
             isPaused={isPaused}
+            progress={progress.progress}
+            remainingMs={progress.remainingMs}
             speed={speed}
             selectedAlgorithm={selectedAlgorithm}
             onSpeedChange={setSpeed}
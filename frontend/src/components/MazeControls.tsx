@@ -5,6 +5,8 @@ import { AlgorithmKind } from '../../../backend/pkg'
 interface MazeControlsProps {
   isGenerating: boolean
   isPaused?: boolean
+  progress?: number
+  remainingMs?: number | null
   speed: number
   selectedAlgorithm: AlgorithmKind
   onSpeedChange: (speed: number) => void
@@ -21,6 +23,8 @@ interface MazeControlsProps {
 export const MazeControls = ({
   isGenerating,
   isPaused = false,
+  progress = 0,
+  remainingMs = null,
   speed,
   selectedAlgorithm,
   onSpeedChange,
@@ -88,6 +92,17 @@ export const MazeControls = ({
           >
             🎬 Générer animé
           </button>
+          {(isGenerating || isPaused) && (
+            <div>
+              <div className="w-full h-2 bg-gray-700 rounded-full overflow-hidden">
+                <div className="h-full bg-green-500 transition-all" style={{ width: `${Math.round(progress * 100)}%` }}></div>
+              </div>
+              <p className="mt-1 text-xs text-gray-400">
+                {Math.round(progress * 100)} %
+                {remainingMs !== null && ` · environ ${formatDuration(remainingMs)} restantes`}
+              </p>
+            </div>
+          )}
           {(isGenerating || isPaused) && (
             <div className="flex gap-2">
               {isPaused ? (
@@ -223,4 +238,10 @@ export const MazeControls = ({
       </div>
     </div>
   )
-}
\ No newline at end of file
+}
+// Durée lisible : "42 s" ou "3 min 05 s"
+const formatDuration = (ms: number) => {
+  const seconds = Math.ceil(ms / 1000)
+  if (seconds < 60) return `${seconds} s`
+  return `${Math.floor(seconds / 60)} min ${String(seconds % 60).padStart(2, '0')} s`
+}
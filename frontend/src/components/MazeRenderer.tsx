@@ -1,5 +1,5 @@
 import { useRef, useEffect, forwardRef, useImperativeHandle } from 'react'
-import { MazeGenerator, WallChange, WallType } from '../../../backend/pkg'
+import { MazeGenerator, WallChange, WallOp, WallType } from '../../../backend/pkg'
 import { MAZE_CONFIG, COLORS, LAYER_COLORS } from '../constants/mazeConfig'
 
 interface MazeRendererProps {
@@ -11,14 +11,19 @@ export interface MazeRendererRef {
   drawFullGrid: () => void
   deleteWallChanges: (changes: WallChange[]) => void
   addWallChanges: (changes: WallChange[]) => void
+  drawFlatChanges: (changes: Uint32Array) => void
   drawCell: (x: number, y: number, color: string) => void
   drawCurrentCell: () => void
 }
 
+// Lecture d'un bit dans un tampon de murs (bit de poids faible en premier)
+const hasBit = (bits: Uint8Array, index: number) => (bits[index >> 3] & (1 << (index & 7))) !== 0
+
 export const MazeRenderer = forwardRef<MazeRendererRef, MazeRendererProps>(
   ({ mazeGenerator, dimensionsKey }, ref) => {
     const canvasRef = useRef<HTMLCanvasElement>(null)
-    const lastLayersRef = useRef<Array<Array<{ x: number; y: number }>>>([])
+    // Cellules de chaque layer actuellement dessinées (clé y * largeur + x)
+    const layerSetsRef = useRef<Array<Set<number>>>([])
 
 
     const drawFullGrid = () => {
@@ -65,10 +70,15 @@ export const MazeRenderer = forwardRef<MazeRendererRef, MazeRendererProps>(
       
       ctx.strokeRect(offset, offset, canvasGridWidth, canvasGridHeight)
 
+      // Vues sans copie sur la mémoire WASM (un bit par cellule d'index y * width + x),
+      // relues à chaque dessin car elles deviennent invalides quand la mémoire grandit
+      const verticalWalls = mazeGenerator.vertical_walls_view()
+      const horizontalWalls = mazeGenerator.horizontal_walls_view()
+
       // Murs intérieurs - vertical_walls[i] = mur à droite de la cellule i
       for (let y = 0; y < gridHeight; y++) {
         for (let x = 0; x < gridWidth; x++) {
-          if (mazeGenerator.has_vertical_wall(x, y)) {
+          if (hasBit(verticalWalls, y * gridWidth + x)) {
             drawWall(x, y, WallType.Vertical, ctx)
           }
         }
@@ -77,7 +87,7 @@ export const MazeRenderer = forwardRef<MazeRendererRef, MazeRendererProps>(
       // Murs intérieurs - horizontal_walls[i] = mur en bas de la cellule i
       for (let y = 0; y < gridHeight; y++) {
         for (let x = 0; x < gridWidth; x++) {
-          if (mazeGenerator.has_horizontal_wall(x, y)) {
+          if (hasBit(horizontalWalls, y * gridWidth + x)) {
             drawWall(x, y, WallType.Horizontal, ctx)
           }
         }
@@ -154,6 +164,20 @@ export const MazeRenderer = forwardRef<MazeRendererRef, MazeRendererProps>(
       })
     }
 
+    // Murs modifiés au format plat de generation_steps : (x, y, type, op) par mur
+    const drawFlatChanges = (changes: Uint32Array) => {
+      const canvas = canvasRef.current
+      if (!canvas || !mazeGenerator) return
+
+      const ctx = canvas.getContext('2d')
+      if (!ctx) return
+
+      for (let i = 0; i + 3 < changes.length; i += 4) {
+        const added = changes[i + 3] === WallOp.Added
+        drawWallWithColor(changes[i], changes[i + 1], changes[i + 2] as WallType, added ? COLORS.WALLS : COLORS.BACKGROUND, ctx, !added)
+      }
+    }
+
     const drawCell = (x: number, y: number, color: string) => {
       const canvas = canvasRef.current
       if (!canvas) return
@@ -176,84 +200,50 @@ export const MazeRenderer = forwardRef<MazeRendererRef, MazeRendererProps>(
     const drawCurrentCell = () => {
       if (!mazeGenerator) return
 
-      const cellLayers = mazeGenerator.get_cell_layers()
-      const lastLayers = lastLayersRef.current
-
-      // Convertir les nouveaux layers en structure utilisable
-      const newLayers: Array<Array<{ x: number; y: number }>> = []
-      const newLayerSets: Array<Set<string>> = []
-
-      // Préparer les nouveaux layers
-      for (let layerIndex = 0; layerIndex < cellLayers.length; layerIndex++) {
-        const layer = cellLayers[layerIndex] as Array<{ x: number; y: number }>
-        const newLayerSet = new Set<string>()
-        const newLayerCells: Array<{ x: number; y: number }> = []
-
-        for (let i = 0; i < layer.length; i++) {
-          const cell = layer[i] as any
-          if (cell && typeof cell.x === 'number' && typeof cell.y === 'number') {
-            const key = `${cell.x},${cell.y}`
-            newLayerSet.add(key)
-            newLayerCells.push({ x: cell.x, y: cell.y })
-          }
-        }
-
-        newLayers.push(newLayerCells)
-        newLayerSets.push(newLayerSet)
-      }
+      // [vider, puis (layer, x, y, entrée) par cellule] : seules les cellules modifiées sont transmises
+      const deltas = mazeGenerator.take_layer_deltas()
+      const layerSets = layerSetsRef.current
+      const gridWidth = mazeGenerator.get_grid_width()
 
-      // Fonction helper : trouver la couleur à afficher pour une cellule donnée
-      // Parcourt les layers du plus haut (priorité) au plus bas
-      const getCellColor = (x: number, y: number, maxLayer: number): string => {
-        const key = `${x},${y}`
-        for (let i = maxLayer; i >= 0; i--) {
-          if (newLayerSets[i] && newLayerSets[i].has(key)) {
+      // Couleur d'une cellule : le layer le plus haut qui la contient l'emporte
+      const getCellColor = (key: number): string => {
+        for (let i = layerSets.length - 1; i >= 0; i--) {
+          if (layerSets[i].has(key)) {
             return LAYER_COLORS[i] || LAYER_COLORS[0]
           }
         }
         return COLORS.BACKGROUND
       }
 
-      // Traiter chaque layer pour détecter les changements
-      for (let layerIndex = 0; layerIndex < Math.max(cellLayers.length, lastLayers.length); layerIndex++) {
-        const lastLayerCells = lastLayers[layerIndex] || []
-        const newLayerCells = newLayers[layerIndex] || []
-
-        // Créer un Set des anciennes cellules
-        const lastLayerSet = new Set<string>()
-        lastLayerCells.forEach(cell => {
-          lastLayerSet.add(`${cell.x},${cell.y}`)
-        })
-
-        const currentLayerSet = newLayerSets[layerIndex] || new Set<string>()
-        const color = LAYER_COLORS[layerIndex] || LAYER_COLORS[0]
-
-        // Dessiner les nouvelles cellules de ce layer
-        newLayerCells.forEach(cell => {
-          const key = `${cell.x},${cell.y}`
-          if (!lastLayerSet.has(key)) {
-            drawCell(cell.x, cell.y, color)
-          }
-        })
-
-        // Pour les cellules qui ont disparu de ce layer, redessiner avec la bonne couleur
-        lastLayerCells.forEach(cell => {
-          const key = `${cell.x},${cell.y}`
-          if (!currentLayerSet.has(key)) {
-            // Trouver quelle couleur devrait avoir cette cellule maintenant
-            const newColor = getCellColor(cell.x, cell.y, layerIndex - 1)
-            drawCell(cell.x, cell.y, newColor)
-          }
-        })
+      if (deltas[0] === 1) {
+        // Nouvel algorithme : effacer les cellules encore colorées
+        const colored = new Set<number>()
+        layerSets.forEach((layer) => layer.forEach((key) => colored.add(key)))
+        layerSets.length = 0
+        colored.forEach((key) => drawCell(key % gridWidth, Math.floor(key / gridWidth), COLORS.BACKGROUND))
       }
 
-      lastLayersRef.current = newLayers
+      for (let i = 1; i + 3 < deltas.length; i += 4) {
+        const layer = deltas[i]
+        const key = deltas[i + 2] * gridWidth + deltas[i + 1]
+        while (layerSets.length <= layer) {
+          layerSets.push(new Set<number>())
+        }
+
+        if (deltas[i + 3] === 1) {
+          layerSets[layer].add(key)
+        } else {
+          layerSets[layer].delete(key)
+        }
+        drawCell(deltas[i + 1], deltas[i + 2], getCellColor(key))
+      }
     }
 
     useImperativeHandle(ref, () => ({
       drawFullGrid,
       deleteWallChanges,
       addWallChanges,
+      drawFlatChanges,
       drawCell,
       drawCurrentCell,
     }))
@@ -261,7 +251,7 @@ export const MazeRenderer = forwardRef<MazeRendererRef, MazeRendererProps>(
     useEffect(() => {
       if (mazeGenerator) {
         drawFullGrid()
-        lastLayersRef.current = []
+        layerSetsRef.current = []
       }
     }, [mazeGenerator, dimensionsKey])
 
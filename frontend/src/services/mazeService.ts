@@ -1,16 +1,16 @@
-import init, { MazeGenerator, AlgorithmKind, WallChange } from '../../../backend/pkg/pathfinding.js'
+import init, { MazeGenerator, AlgorithmKind } from '../../../backend/pkg/pathfinding.js'
 import { GRID_SIZE } from '../constants/mazeConfig'
 
-interface GenerationStepResult {
-  isFinished: boolean;
-  changes: WallChange[];
-}
+// Clé localStorage de l'instantané d'une génération inachevée
+const SNAPSHOT_KEY = 'maze-generation-snapshot'
 
 // Simple service class instead of complex hook
 export class MazeService {
   private generator: MazeGenerator | null = null
   private isInitialized = false
   private animationId: number | null = null
+  // Temps d'animation écoulé depuis le début de la génération, pauses exclues (pour l'estimation du temps restant)
+  private elapsedMs = 0
 
   async initialize() {
     if (this.isInitialized) return this.generator
@@ -19,9 +19,55 @@ export class MazeService {
     this.generator = new MazeGenerator(GRID_SIZE.DEFAULT_WIDTH, GRID_SIZE.DEFAULT_HEIGHT)
     this.generator.clear_grid()
     this.isInitialized = true
+
+    // Sauvegarder la génération en cours à la fermeture ou au rechargement de la page
+    window.addEventListener('pagehide', () => this.saveSnapshot())
     return this.generator
   }
 
+  // Instantané de la génération inachevée dans localStorage (supprimé s'il n'y en a pas)
+  saveSnapshot() {
+    if (!this.generator) return
+    if (this.generator.algorithm_kind() === undefined || this.generator.is_finished()) {
+      this.clearSnapshot()
+      return
+    }
+
+    try {
+      const bytes = this.generator.snapshot()
+      let binary = ''
+      for (let i = 0; i < bytes.length; i += 0x8000) {
+        binary += String.fromCharCode(...bytes.subarray(i, i + 0x8000))
+      }
+      localStorage.setItem(SNAPSHOT_KEY, btoa(binary))
+    } catch (error) {
+      // Quota dépassé sur une très grande grille : la génération ne sera pas reprise
+      console.error(error)
+      this.clearSnapshot()
+    }
+  }
+
+  // Reprendre la génération sauvegardée ; retourne son algorithme, ou null s'il n'y en a pas
+  restoreSnapshot(): AlgorithmKind | null {
+    const saved = localStorage.getItem(SNAPSHOT_KEY)
+    if (!this.generator || !saved) return null
+
+    try {
+      this.generator.restore(Uint8Array.from(atob(saved), (char) => char.charCodeAt(0)))
+      this.elapsedMs = 0
+      return this.generator.algorithm_kind() ?? null
+    } catch (error) {
+      // Instantané corrompu ou d'une version précédente : l'oublier
+      console.error(error)
+      this.clearSnapshot()
+      return null
+    }
+  }
+
+  clearSnapshot() {
+    localStorage.removeItem(SNAPSHOT_KEY)
+  }
+
   generateInstant(algorithm: AlgorithmKind) {
     if (!this.generator) return
     this.stopAnimation()
@@ -32,9 +78,10 @@ export class MazeService {
     if (!this.generator) return
     this.stopAnimation()
     this.generator.start_generation(algorithm)
+    this.elapsedMs = 0
   }
 
-  startAnimation(onStep: (changes: WallChange[]) => void, onComplete: () => void, getCurrentSpeed: () => number) {
+  startAnimation(onStep: (changes: Uint32Array) => void, onComplete: () => void, getCurrentSpeed: () => number) {
     if (!this.generator) return
 
     const MAX_STEPS_PER_FRAME = 150 // Limite pour garder l'UI fluide
@@ -47,6 +94,7 @@ export class MazeService {
 
       const deltaTime = currentTime - lastTime
       lastTime = currentTime
+      this.elapsedMs += deltaTime
 
       // Calcul de la vitesse : stepsPerSecond = 1.07 ^ speed
       const speed = getCurrentSpeed()
@@ -57,21 +105,21 @@ export class MazeService {
       const stepsToExecute = Math.min(Math.floor(stepDebt), MAX_STEPS_PER_FRAME)
       stepDebt -= stepsToExecute
 
-      const allChanges: WallChange[] = []
-      let isFinished = false
-
-      for (let i = 0; i < stepsToExecute; i++) {
-        const stepResult = this.generator.generation_step_with_changes() as GenerationStepResult
-        allChanges.push(...stepResult.changes)
-
-        if (stepResult.isFinished) {
-          isFinished = true
-          break
-        }
+      // Un seul appel WASM par image : (x, y, type, op) pour chaque mur modifié
+      // Lève une erreur si aucune génération n'est démarrée (ex : grille redimensionnée entre-temps)
+      let changes: Uint32Array
+      try {
+        changes = stepsToExecute > 0 ? this.generator.generation_steps(stepsToExecute) : new Uint32Array()
+      } catch (error) {
+        console.error(error)
+        onComplete()
+        this.animationId = null
+        return
       }
+      const isFinished = this.generator.is_finished()
 
       if (stepsToExecute > 0) {
-        onStep(allChanges)
+        onStep(changes)
       }
 
       if (isFinished) {
@@ -97,6 +145,15 @@ export class MazeService {
     this.pauseAnimation()
   }
 
+  // Avancement (0 à 1) et temps restant estimé en millisecondes (null tant qu'il est inconnu)
+  getProgress() {
+    if (!this.generator) return { progress: 0, remainingMs: null }
+    return {
+      progress: this.generator.progress(),
+      remainingMs: this.generator.estimate_remaining(this.elapsedMs) ?? null
+    }
+  }
+
   getCellLayers() {
     if (!this.generator) return []
     const layers = this.generator.get_cell_layers()